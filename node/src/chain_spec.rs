@@ -1,12 +1,16 @@
 use node_template_runtime::{
+	pallet_valueflows_agent::{ConceptId, ConceptSchemeId, RegistrationMode, UnitId},
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	SystemConfig, ValueflowsAgentConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::{
+	traits::{IdentifyAccount, Verify},
+	FixedI64,
+};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -59,6 +63,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				Default::default(),
 				true,
 			)
 		},
@@ -107,6 +112,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				Default::default(),
 				true,
 			)
 		},
@@ -124,12 +130,115 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 	))
 }
 
+/// A development chain pre-populated with ValueFlows agents and knowledge-layer records.
+pub fn valueflows_demo_config() -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		"ValueFlows Demo",
+		// ID
+		"valueflows_demo",
+		ChainType::Development,
+		move || {
+			testnet_genesis(
+				wasm_binary,
+				// Initial PoA authorities
+				vec![authority_keys_from_seed("Alice")],
+				// Sudo account
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				// Pre-funded accounts
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+				],
+				valueflows_demo_genesis(),
+				true,
+			)
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
+		None,
+		None,
+		// Properties
+		None,
+		// Extensions
+		None,
+	))
+}
+
+/// Agents, units, places and specifications of a small food cooperative.
+fn valueflows_demo_genesis() -> ValueflowsAgentConfig {
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+	let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+	let text = |s: &str| s.as_bytes().to_vec();
+
+	ValueflowsAgentConfig {
+		agents: vec![alice.clone(), bob.clone(), charlie.clone()],
+		registration_mode: RegistrationMode::Open,
+		units: vec![
+			(alice.clone(), text("kilogram"), text("kg")),
+			(alice.clone(), text("gram"), text("g")),
+			(alice.clone(), text("litre"), text("l")),
+			(alice.clone(), text("hour"), text("h")),
+			(alice.clone(), text("each"), text("ea")),
+		],
+		spatial_things: vec![
+			(
+				bob.clone(),
+				text("Farm"),
+				Some(text("fields")),
+				None,
+				Some(FixedI64::from_float(52.5200)),
+				Some(FixedI64::from_float(13.4050)),
+				None,
+			),
+			(
+				charlie.clone(),
+				text("Market"),
+				None,
+				Some(text("Main St 1")),
+				Some(FixedI64::from_float(52.5163)),
+				Some(FixedI64::from_float(13.3777)),
+				None,
+			),
+		],
+		process_specifications: vec![
+			(bob.clone(), text("Harvest"), None),
+			(bob.clone(), text("Pack"), Some(text("in crates"))),
+			(charlie.clone(), text("Deliver"), None),
+		],
+		resource_specifications: vec![
+			(bob.clone(), text("Apple"), None, vec![ConceptId(1)], Some(UnitId(0)), None),
+			(bob.clone(), text("Carrot"), None, vec![ConceptId(2)], Some(UnitId(0)), None),
+			(charlie.clone(), text("Delivery"), None, vec![ConceptId(3)], None, Some(UnitId(3))),
+		],
+		concept_schemes: vec![(bob, text("Products")), (charlie, text("Services"))],
+		concepts: vec![
+			(ConceptSchemeId(0), text("Food"), None),
+			(ConceptSchemeId(0), text("Fruit"), Some(ConceptId(0))),
+			(ConceptSchemeId(0), text("Vegetable"), Some(ConceptId(0))),
+			(ConceptSchemeId(1), text("Transport"), None),
+		],
+		archived_process_specifications: vec![],
+		archived_resource_specifications: vec![],
+	}
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	valueflows_agent: ValueflowsAgentConfig,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -152,5 +261,6 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		valueflows_agent,
 	}
 }
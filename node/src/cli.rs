@@ -7,6 +7,23 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Serve the ValueFlows GraphQL API on this port of the local interface.
+	#[arg(long, value_name = "PORT")]
+	pub graphql_port: Option<u16>,
+
+	/// Secret URI of the account signing GraphQL mutations. Mutations are disabled without it.
+	#[arg(long, value_name = "SECRET_URI", requires_all = ["graphql_port", "graphql_token"])]
+	pub graphql_signer: Option<String>,
+
+	/// Bearer token GraphQL clients must present to run mutations.
+	#[arg(long, value_name = "TOKEN", requires = "graphql_signer")]
+	pub graphql_token: Option<String>,
+
+	/// Origin browsers may send GraphQL requests from, e.g. `http://localhost:3000`. May be given
+	/// several times. Requests from other web pages are refused.
+	#[arg(long, value_name = "ORIGIN", requires = "graphql_port")]
+	pub graphql_allowed_origin: Vec<String>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -27,6 +44,12 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the ValueFlows records at a given block as JSON-LD.
+	ExportJsonLd(crate::jsonld::ExportJsonLdCmd),
+
+	/// Add ValueFlows records in JSON-LD to the agent pallet genesis config of a chain spec.
+	ImportJsonLd(crate::jsonld::ImportJsonLdCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
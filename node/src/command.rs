@@ -2,12 +2,13 @@ use crate::{
 	benchmarking::{inherent_benchmark_data, RemarkBuilder, TransferKeepAliveBuilder},
 	chain_spec,
 	cli::{Cli, Subcommand},
-	service,
+	graphql, service,
 };
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
 use node_template_runtime::{Block, EXISTENTIAL_DEPOSIT};
 use sc_cli::{ChainSpec, RuntimeVersion, SubstrateCli};
 use sc_service::PartialComponents;
+use sp_core::{sr25519, Pair};
 use sp_keyring::Sr25519Keyring;
 
 impl SubstrateCli for Cli {
@@ -38,6 +39,7 @@ impl SubstrateCli for Cli {
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
 		Ok(match id {
 			"dev" => Box::new(chain_spec::development_config()?),
+			"valueflows-demo" => Box::new(chain_spec::valueflows_demo_config()?),
 			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
 			path =>
 				Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
@@ -81,6 +83,14 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ExportJsonLd(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, .. } = service::new_partial(&config)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
+		Some(Subcommand::ImportJsonLd(cmd)) => cmd.run(),
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -202,9 +212,26 @@ pub fn run() -> sc_cli::Result<()> {
 			runner.sync_run(|config| cmd.run::<Block>(&config))
 		},
 		None => {
+			let graphql = cli
+				.graphql_port
+				.map(|port| -> sc_cli::Result<_> {
+					let signer = cli
+						.graphql_signer
+						.as_ref()
+						.map(|suri| sr25519::Pair::from_string(suri, None))
+						.transpose()
+						.map_err(|e| format!("Invalid GraphQL signer: {:?}", e))?;
+					Ok(graphql::Config {
+						port,
+						signer,
+						token: cli.graphql_token.clone(),
+						allowed_origins: cli.graphql_allowed_origin.clone(),
+					})
+				})
+				.transpose()?;
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, graphql).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
@@ -0,0 +1,226 @@
+//! Off-chain storage for content referenced by CID from the agent pallet, such as resource
+//! specification images.
+//!
+//! Content is kept on the node's filesystem under `<base-path>/chains/<chain>/content`, one file
+//! per CID, and is served through the `vf_uploadContent` and `vf_fetchContent` RPC methods.
+//! Uploaded bytes are only accepted when they hash to the given CID, so a client can trust
+//! whatever it fetches by comparing against the CID stored on chain.
+
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::pallet_valueflows_agent::cid;
+use sc_rpc_api::DenyUnsafe;
+use sp_core::Bytes;
+
+/// The largest piece of content accepted by the store, the largest an image on chain can be.
+pub const MAX_CONTENT_SIZE: usize = cid::MAX_CONTENT_SIZE as usize;
+
+/// Error returned by the [`ContentStore`].
+#[derive(Debug)]
+pub enum Error {
+	/// The CID is malformed or uses an unsupported hash function.
+	InvalidCid,
+	/// The content does not hash to the CID it was uploaded under.
+	HashMismatch,
+	/// The content is larger than [`MAX_CONTENT_SIZE`].
+	TooLarge,
+	/// Reading or writing the content failed.
+	Io(io::Error),
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Error::InvalidCid => write!(f, "Invalid or unsupported CID"),
+			Error::HashMismatch => write!(f, "Content does not match the CID"),
+			Error::TooLarge => write!(f, "Content exceeds {} bytes", MAX_CONTENT_SIZE),
+			Error::Io(e) => write!(f, "I/O error: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+	fn from(e: io::Error) -> Self {
+		Error::Io(e)
+	}
+}
+
+/// Content addressed store backed by a directory.
+pub struct ContentStore {
+	root: PathBuf,
+}
+
+impl ContentStore {
+	/// Open the store in `root`, creating the directory if needed.
+	pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+		let root = root.as_ref().to_path_buf();
+		fs::create_dir_all(&root)?;
+		Ok(Self { root })
+	}
+
+	/// Store `content` under `cid` after checking that it hashes to it.
+	pub fn put(&self, cid: &[u8], content: &[u8]) -> Result<(), Error> {
+		let (hasher, digest) = cid::parse(cid).ok_or(Error::InvalidCid)?;
+		if content.len() > MAX_CONTENT_SIZE {
+			return Err(Error::TooLarge)
+		}
+		if hasher.digest(content) != digest {
+			return Err(Error::HashMismatch)
+		}
+
+		// Write to a temporary file first so readers never see partial content.
+		let path = self.path(cid);
+		let tmp = path.with_extension("tmp");
+		fs::write(&tmp, content)?;
+		fs::rename(tmp, path)?;
+		Ok(())
+	}
+
+	/// Get the content stored under `cid`, if any.
+	pub fn get(&self, cid: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+		cid::parse(cid).ok_or(Error::InvalidCid)?;
+		match fs::read(self.path(cid)) {
+			Ok(content) => Ok(Some(content)),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	fn path(&self, cid: &[u8]) -> PathBuf {
+		self.root.join(hex(cid))
+	}
+}
+
+fn hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// RPC methods for uploading and fetching off-chain content.
+#[rpc(client, server)]
+pub trait ContentApi {
+	/// Store `content` under the binary `cid`. The content must hash to the CID.
+	#[method(name = "vf_uploadContent")]
+	fn upload_content(&self, cid: Bytes, content: Bytes) -> RpcResult<()>;
+
+	/// Fetch the content stored under the binary `cid`.
+	#[method(name = "vf_fetchContent")]
+	fn fetch_content(&self, cid: Bytes) -> RpcResult<Option<Bytes>>;
+}
+
+/// Implementation of [`ContentApiServer`] on top of a [`ContentStore`].
+pub struct Content {
+	store: Arc<ContentStore>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Content {
+	/// Create a new RPC handler serving `store`.
+	pub fn new(store: Arc<ContentStore>, deny_unsafe: DenyUnsafe) -> Self {
+		Self { store, deny_unsafe }
+	}
+}
+
+fn rpc_error(e: Error) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(1, e.to_string(), None::<()>)).into()
+}
+
+#[async_trait]
+impl ContentApiServer for Content {
+	fn upload_content(&self, cid: Bytes, content: Bytes) -> RpcResult<()> {
+		// Uploads write to disk, so only trusted callers may use them.
+		self.deny_unsafe.check_if_safe()?;
+		self.store.put(&cid, &content).map_err(rpc_error)
+	}
+
+	fn fetch_content(&self, cid: Bytes) -> RpcResult<Option<Bytes>> {
+		self.store.get(&cid).map(|content| content.map(Into::into)).map_err(rpc_error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cid::{cid_of, Hasher};
+
+	fn store(name: &str) -> ContentStore {
+		let root = std::env::temp_dir().join(format!("vf-content-{}-{}", name, std::process::id()));
+		let _ = fs::remove_dir_all(&root);
+		ContentStore::open(root).unwrap()
+	}
+
+	#[test]
+	fn put_stores_content_matching_the_cid() {
+		let store = store("matching");
+		for hasher in [Hasher::Sha2_256, Hasher::Blake2b256] {
+			let cid = cid_of(b"image", hasher);
+			store.put(&cid, b"image").unwrap();
+			assert_eq!(store.get(&cid).unwrap(), Some(b"image".to_vec()));
+		}
+	}
+
+	#[test]
+	fn put_rejects_content_not_matching_the_cid() {
+		let store = store("mismatched");
+		let cid = cid_of(b"image", Hasher::Blake2b256);
+		assert!(matches!(store.put(&cid, b"other"), Err(Error::HashMismatch)));
+		assert_eq!(store.get(&cid).unwrap(), None);
+	}
+
+	#[test]
+	fn put_rejects_invalid_cids() {
+		let store = store("invalid");
+		let mut identity = cid_of(b"image", Hasher::Sha2_256);
+		identity[2] = 0x00;
+		let invalid: [&[u8]; 3] = [b"not a cid", &[0x01, 0x55], &identity];
+		for cid in invalid {
+			assert!(matches!(store.put(cid, b"image"), Err(Error::InvalidCid)));
+			assert!(matches!(store.get(cid), Err(Error::InvalidCid)));
+		}
+	}
+
+	#[test]
+	fn largest_content_fits_the_default_rpc_request_size() {
+		// The default of `--rpc-max-request-size`, in bytes.
+		const RPC_MAX_REQUEST_SIZE: usize = 15 * 1024 * 1024;
+
+		let store = store("largest");
+		let content = vec![0; MAX_CONTENT_SIZE];
+		let cid = cid_of(&content, Hasher::Blake2b256);
+		let request = serde_json::json!({
+			"jsonrpc": "2.0",
+			"id": u32::MAX,
+			"method": "vf_uploadContent",
+			"params": [Bytes(cid.clone()), Bytes(content.clone())],
+		});
+		assert!(serde_json::to_vec(&request).unwrap().len() <= RPC_MAX_REQUEST_SIZE);
+
+		store.put(&cid, &content).unwrap();
+		assert_eq!(store.get(&cid).unwrap(), Some(content));
+	}
+
+	#[test]
+	fn put_rejects_too_large_content() {
+		let store = store("too-large");
+		let content = vec![0; MAX_CONTENT_SIZE + 1];
+		let cid = cid_of(&content, Hasher::Blake2b256);
+		assert!(matches!(store.put(&cid, &content), Err(Error::TooLarge)));
+		assert_eq!(store.get(&cid).unwrap(), None);
+	}
+
+	#[test]
+	fn get_returns_none_for_missing_content() {
+		let store = store("missing");
+		assert_eq!(store.get(&cid_of(b"image", Hasher::Sha2_256)).unwrap(), None);
+	}
+}
@@ -0,0 +1,1063 @@
+//! GraphQL endpoint serving the agent pallet records.
+//!
+//! The queries follow the ValueFlows GraphQL schema, [vf-graphql]. The `agent`, `unit`,
+//! `spatialThing`, `processSpecification` and `resourceSpecification` queries, and their plural
+//! forms, are served from the state of the best block with the vf-graphql field names, so that
+//! existing ValueFlows front-ends can read the records. Records are identified by their local id in
+//! decimal, agents by their SS58 address. The plural forms return Relay connections, paged with
+//! `first`, `after`, `last` and `before`.
+//!
+//! When the node is given a signer, the `create*` mutations sign and submit the matching
+//! extrinsics with that account. Ids are only assigned once the extrinsic is included in a block,
+//! so the mutations wait for that block and return the created record as it is there, in the
+//! `*Response` types of vf-graphql, e.g. `UnitResponse { unit }`. Notes are submitted as preimages
+//! in a transaction preceding the one creating the record.
+//!
+//! Requests must be sent as `application/json`, and browsers may only send them from the origins
+//! the node is configured to allow, so that other web pages cannot reach the endpoint on the
+//! local interface. Mutations further require the bearer token the node is configured with.
+//!
+//! [vf-graphql]: https://github.com/valueflows/vf-graphql
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use async_graphql::{
+	connection::{query, Connection, CursorType, Edge},
+	http::GraphiQLSource,
+	Context, EmptySubscription, Error, InputObject, Object, OutputType, Result, Schema,
+	SimpleObject, ID,
+};
+use codec::{Decode, Encode};
+use frame_system::{EventRecord, Phase};
+use futures::{lock::Mutex, StreamExt};
+use hyper::{
+	header::{AUTHORIZATION, CONTENT_TYPE, ORIGIN},
+	service::{make_service_fn, service_fn},
+	Body, Method, Request, Response, Server, StatusCode,
+};
+use node_template_runtime::{
+	self as runtime,
+	opaque::Block,
+	pallet_valueflows_agent::{self as agent, ConceptId, UnitId},
+	AccountId, PreimageCall, Runtime, RuntimeCall, RuntimeEvent,
+};
+use sc_client_api::{BlockBackend, StorageProvider};
+use sc_transaction_pool_api::{
+	InPoolTransaction, TransactionPool, TransactionSource, TransactionStatus,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{crypto::Ss58Codec, hashing::blake2_256, sr25519, storage::StorageKey, Pair, H256};
+use sp_runtime::{
+	generic::{BlockId, Era},
+	traits::Get,
+	BoundedVec, FixedI64, OpaqueExtrinsic, SaturatedConversion,
+};
+use substrate_frame_rpc_system::AccountNonceApi;
+
+use crate::{
+	indexer::storage_prefix,
+	service::FullClient,
+	storage::{AgentStorage, Hasher},
+};
+
+type Pool = sc_transaction_pool::FullPool<Block, FullClient>;
+
+/// The GraphQL schema served by the node.
+pub(crate) type VfSchema = Schema<Query, Mutation, EmptySubscription>;
+
+/// Configuration of the GraphQL endpoint.
+pub struct Config {
+	/// Port to listen on, on the local interface.
+	pub port: u16,
+	/// Account signing the extrinsics submitted by mutations.
+	pub signer: Option<sr25519::Pair>,
+	/// Bearer token clients must present to run mutations.
+	pub token: Option<String>,
+	/// Origins browsers may send requests from.
+	pub allowed_origins: Vec<String>,
+}
+
+/// Who may use the endpoint.
+pub(crate) struct Access {
+	/// Bearer token clients must present to run mutations. Mutations are refused without it.
+	pub token: Option<String>,
+	/// Origins browsers may send requests from. Requests without an `Origin` header do not come
+	/// from a web page and are served.
+	pub allowed_origins: Vec<String>,
+}
+
+/// Marks requests presenting the bearer token of the endpoint.
+struct Authorized;
+
+/// Build the schema reading from `client` and submitting mutations to `pool`.
+pub(crate) fn schema(
+	client: Arc<FullClient>,
+	pool: Arc<Pool>,
+	signer: Option<sr25519::Pair>,
+) -> VfSchema {
+	let signer = signer.map(|pair| Signer { pair, submitting: Mutex::new(()) });
+	Schema::build(Query, Mutation, EmptySubscription)
+		.data(State { client, pool, signer })
+		.finish()
+}
+
+/// Serve `schema` over HTTP on `addr` to the clients `access` allows, with GraphiQL on `GET`
+/// requests. The origin of the endpoint itself is allowed for GraphiQL.
+pub(crate) async fn serve(addr: SocketAddr, schema: VfSchema, mut access: Access) {
+	access
+		.allowed_origins
+		.extend([format!("http://{}", addr), format!("http://localhost:{}", addr.port())]);
+	let access = Arc::new(access);
+	let make_service = make_service_fn(move |_| {
+		let schema = schema.clone();
+		let access = access.clone();
+		async move {
+			Ok::<_, Infallible>(service_fn(move |req| handle(schema.clone(), access.clone(), req)))
+		}
+	});
+	log::info!("GraphQL endpoint listening on http://{}", addr);
+	if let Err(e) = Server::bind(&addr).serve(make_service).await {
+		log::error!("GraphQL endpoint failed: {}", e);
+	}
+}
+
+async fn handle(
+	schema: VfSchema,
+	access: Arc<Access>,
+	req: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+	let method = req.method().clone();
+	let response = match (method, check(&access, &req)) {
+		(_, Err(status)) => Response::builder().status(status).body(Body::empty()),
+		(Method::GET, _) => Response::builder()
+			.header(CONTENT_TYPE, "text/html")
+			.body(GraphiQLSource::build().endpoint("/").finish().into()),
+		(Method::POST, Ok(authorized)) => {
+			let request = hyper::body::to_bytes(req.into_body())
+				.await
+				.ok()
+				.and_then(|body| serde_json::from_slice::<async_graphql::Request>(&body).ok());
+			match request {
+				Some(mut request) => {
+					if authorized {
+						request = request.data(Authorized);
+					}
+					let response = schema.execute(request).await;
+					Response::builder()
+						.header(CONTENT_TYPE, "application/json")
+						.body(serde_json::to_vec(&response).unwrap_or_default().into())
+				},
+				None => Response::builder()
+					.status(StatusCode::BAD_REQUEST)
+					.body("Invalid GraphQL request".into()),
+			}
+		},
+		_ => Response::builder().status(StatusCode::METHOD_NOT_ALLOWED).body(Body::empty()),
+	};
+	Ok(response.expect("responses are built from valid parts; qed"))
+}
+
+/// Check `req` against `access`, returning whether it presents the bearer token, or the status to
+/// refuse it with.
+fn check(access: &Access, req: &Request<Body>) -> Result<bool, StatusCode> {
+	let header = |name| req.headers().get(name).and_then(|value| value.to_str().ok());
+
+	if let Some(origin) = header(ORIGIN) {
+		if !access.allowed_origins.iter().any(|allowed| allowed == origin) {
+			return Err(StatusCode::FORBIDDEN)
+		}
+	}
+	if req.method() == Method::POST {
+		let media_type = header(CONTENT_TYPE).and_then(|value| value.split(';').next());
+		if !media_type.map_or(false, |t| t.trim().eq_ignore_ascii_case("application/json")) {
+			return Err(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+		}
+	}
+
+	let presented = header(AUTHORIZATION).and_then(|value| value.strip_prefix("Bearer "));
+	Ok(match (&access.token, presented) {
+		(Some(token), Some(presented)) => constant_time_eq(token.as_bytes(), presented.as_bytes()),
+		_ => false,
+	})
+}
+
+/// Compare `a` and `b` in a time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Account signing mutations.
+struct Signer {
+	pair: sr25519::Pair,
+	/// Held while submitting, so that concurrent mutations do not pick the same nonces.
+	submitting: Mutex<()>,
+}
+
+/// Data shared by the resolvers.
+struct State {
+	client: Arc<FullClient>,
+	pool: Arc<Pool>,
+	signer: Option<Signer>,
+}
+
+impl State {
+	fn get(ctx: &Context<'_>) -> &Self {
+		ctx.data_unchecked::<Self>()
+	}
+
+	/// The records at the best block.
+	fn records(&self) -> Records<'_> {
+		self.records_at(self.client.info().best_hash)
+	}
+
+	/// The records at the block `at`.
+	fn records_at(&self, at: H256) -> Records<'_> {
+		Records { storage: AgentStorage::new(&self.client, at) }
+	}
+
+	/// Sign `calls` and submit them in order, then wait for the last one to be included in a
+	/// block. Returns that block and the events of the last transaction.
+	async fn submit(
+		ctx: &Context<'_>,
+		calls: Vec<RuntimeCall>,
+	) -> Result<(H256, Vec<RuntimeEvent>)> {
+		let state = Self::get(ctx);
+		ctx.data_opt::<Authorized>()
+			.ok_or_else(|| Error::new("Mutations require the bearer token of the node"))?;
+		let signer = state
+			.signer
+			.as_ref()
+			.ok_or_else(|| Error::new("Mutations require the node to have a GraphQL signer"))?;
+		let account = AccountId::from(signer.pair.public());
+		let Some((last, preceding)) = calls.split_last() else {
+			return Err(Error::new("Nothing to submit"))
+		};
+
+		let (transaction_hash, mut status) = {
+			let _submitting = signer.submitting.lock().await;
+			let best_hash = state.client.info().best_hash;
+			let at = BlockId::hash(best_hash);
+			let mut nonce = next_nonce(
+				state.client.runtime_api().account_nonce(best_hash, account.clone())?,
+				&account,
+				state.pool.ready().filter_map(|tx| tx.provides().first().cloned()),
+			);
+			let mut sign = |call: &RuntimeCall| -> OpaqueExtrinsic {
+				let extrinsic = signed_extrinsic(&state.client, &signer.pair, call.clone(), nonce);
+				nonce += 1;
+				extrinsic.into()
+			};
+			for call in preceding {
+				state.pool.submit_one(&at, TransactionSource::Local, sign(call)).await?;
+			}
+			let extrinsic = sign(last);
+			let transaction_hash = state.pool.hash_of(&extrinsic);
+			let status =
+				state.pool.submit_and_watch(&at, TransactionSource::Local, extrinsic).await?;
+			(transaction_hash, status)
+		};
+
+		let block = loop {
+			match status.next().await {
+				Some(TransactionStatus::InBlock(block) | TransactionStatus::Finalized(block)) =>
+					break block,
+				Some(
+					TransactionStatus::Usurped(_) |
+					TransactionStatus::Dropped |
+					TransactionStatus::Invalid,
+				) |
+				None => return Err(Error::new("The transaction was dropped from the pool")),
+				Some(_) => {},
+			}
+		};
+		let index = state
+			.client
+			.block_body(block)?
+			.and_then(|body| body.iter().position(|xt| state.pool.hash_of(xt) == transaction_hash))
+			.ok_or_else(|| Error::new("The transaction is missing from its block"))?;
+		let events = state
+			.client
+			.storage(block, &StorageKey(storage_prefix("System", "Events")))?
+			.map(|events| Vec::<EventRecord<RuntimeEvent, H256>>::decode(&mut &events.0[..]))
+			.transpose()?
+			.unwrap_or_default()
+			.into_iter()
+			.filter(|record| record.phase == Phase::ApplyExtrinsic(index as u32))
+			.map(|record| record.event)
+			.collect::<Vec<_>>();
+		for event in &events {
+			if let RuntimeEvent::System(frame_system::Event::ExtrinsicFailed {
+				dispatch_error,
+				..
+			}) = event
+			{
+				return Err(Error::new(format!("The transaction failed: {:?}", dispatch_error)))
+			}
+		}
+
+		Ok((block, events))
+	}
+}
+
+/// The agent pallet records at a block, as GraphQL objects.
+struct Records<'a> {
+	storage: AgentStorage<'a>,
+}
+
+impl Records<'_> {
+	/// The record stored under `id` in the `item` map, converted with `convert`.
+	fn get<I: Encode + Copy, R: Decode, N>(
+		&self,
+		item: &str,
+		id: I,
+		convert: impl FnOnce(&Self, I, R) -> Result<N>,
+	) -> Result<Option<N>> {
+		self.storage
+			.value(item, Hasher::Twox64Concat, id)?
+			.map(|record| convert(self, id, record))
+			.transpose()
+	}
+
+	fn unit(&self, id: UnitId) -> Result<Option<Unit>> {
+		let unit =
+			self.storage
+				.value::<_, agent::Unit<Runtime>>("Units", Hasher::Twox64Concat, id)?;
+		Ok(unit.map(|unit| Unit::new(id, unit)))
+	}
+
+	fn spatial_thing(
+		&self,
+		id: agent::SpatialThingId,
+		spatial_thing: agent::SpatialThing<Runtime>,
+	) -> Result<SpatialThing> {
+		Ok(SpatialThing {
+			id: graphql_id(id.0),
+			name: string(&spatial_thing.name),
+			note: self.storage.text(spatial_thing.note)?,
+			mappable_address: self.storage.text(spatial_thing.mappable_address)?,
+			lat: spatial_thing.lat.map(FixedI64::to_float),
+			long: spatial_thing.long.map(FixedI64::to_float),
+			alt: spatial_thing.alt.map(FixedI64::to_float),
+		})
+	}
+
+	fn process_specification(
+		&self,
+		id: agent::ProcessSpecificationId,
+		process_spec: agent::ProcessSpecification<Runtime>,
+	) -> Result<ProcessSpecification> {
+		Ok(ProcessSpecification {
+			id: graphql_id(id.0),
+			name: string(&process_spec.name),
+			note: self.storage.text(process_spec.note)?,
+			revision: process_spec.revision,
+			archived_at: process_spec.archived_at,
+		})
+	}
+
+	fn resource_specification(
+		&self,
+		id: agent::ResourceSpecificationId,
+		resource_spec: agent::ResourceSpecification<Runtime>,
+	) -> Result<ResourceSpecification> {
+		let image_list: Vec<_> = resource_spec.images.iter().map(|image| hex(&image.cid)).collect();
+		Ok(ResourceSpecification {
+			id: graphql_id(id.0),
+			name: string(&resource_spec.name),
+			note: self.storage.text(resource_spec.note)?,
+			image: image_list.first().cloned(),
+			image_list,
+			resource_classified_as: resource_spec
+				.resource_classified_as
+				.iter()
+				.map(|concept_id| graphql_id(concept_id.0))
+				.collect(),
+			default_unit_of_resource: resource_spec
+				.default_unit_of_resource_id
+				.map(|id| self.unit(id))
+				.transpose()?
+				.flatten(),
+			default_unit_of_effort: resource_spec
+				.default_unit_of_effort_id
+				.map(|id| self.unit(id))
+				.transpose()?
+				.flatten(),
+			revision: resource_spec.revision,
+			archived_at: resource_spec.archived_at,
+		})
+	}
+}
+
+/// The nonce of the next transaction of `account`, following its `nonce` on chain and the
+/// transactions of `account` ready in the pool, given by the first tag each of them `provides`.
+/// Transactions leaving the pool without being included thus leave no gap, as in the
+/// `system_accountNextIndex` RPC.
+fn next_nonce(mut nonce: u32, account: &AccountId, provides: impl Iterator<Item = Vec<u8>>) -> u32 {
+	for tag in provides {
+		if tag == (account, nonce).encode() {
+			nonce += 1;
+		}
+	}
+	nonce
+}
+
+/// Page through `nodes`, sorted by their cursors, as a Relay connection.
+async fn connection<C, N>(
+	mut nodes: Vec<(C, N)>,
+	after: Option<String>,
+	before: Option<String>,
+	first: Option<i32>,
+	last: Option<i32>,
+) -> Result<Connection<C, N>>
+where
+	C: CursorType + Ord + Send + Sync,
+	N: OutputType,
+{
+	nodes.sort_by(|(a, _), (b, _)| a.cmp(b));
+	query(after, before, first, last, |after, before, first, last| async move {
+		let mut start = after.map_or(0, |after| nodes.partition_point(|(c, _)| *c <= after));
+		let mut end =
+			before.map_or(nodes.len(), |before| nodes.partition_point(|(c, _)| *c < before));
+		end = end.max(start);
+		if let Some(first) = first {
+			end = end.min(start + first);
+		}
+		if let Some(last) = last {
+			start = start.max(end.saturating_sub(last));
+		}
+
+		let mut connection = Connection::new(start > 0, end < nodes.len());
+		connection
+			.edges
+			.extend(nodes.drain(start..end).map(|(c, node)| Edge::new(c, node)));
+		Ok::<_, Error>(connection)
+	})
+	.await
+}
+
+/// Sign `call` with `signer`, valid from the best block on.
+fn signed_extrinsic(
+	client: &FullClient,
+	signer: &sr25519::Pair,
+	call: RuntimeCall,
+	nonce: u32,
+) -> runtime::UncheckedExtrinsic {
+	let genesis_hash = client.block_hash(0).ok().flatten().expect("Genesis block exists; qed");
+	let info = client.info();
+
+	let period = runtime::BlockHashCount::get()
+		.checked_next_power_of_two()
+		.map(|c| c / 2)
+		.unwrap_or(2) as u64;
+	let extra: runtime::SignedExtra = (
+		frame_system::CheckNonZeroSender::<Runtime>::new(),
+		frame_system::CheckSpecVersion::<Runtime>::new(),
+		frame_system::CheckTxVersion::<Runtime>::new(),
+		frame_system::CheckGenesis::<Runtime>::new(),
+		frame_system::CheckEra::<Runtime>::from(Era::mortal(
+			period,
+			info.best_number.saturated_into(),
+		)),
+		frame_system::CheckNonce::<Runtime>::from(nonce),
+		frame_system::CheckWeight::<Runtime>::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+	);
+
+	let raw_payload = runtime::SignedPayload::from_raw(
+		call.clone(),
+		extra.clone(),
+		(
+			(),
+			runtime::VERSION.spec_version,
+			runtime::VERSION.transaction_version,
+			genesis_hash,
+			info.best_hash,
+			(),
+			(),
+			(),
+		),
+	);
+	let signature = raw_payload.using_encoded(|e| signer.sign(e));
+
+	runtime::UncheckedExtrinsic::new_signed(
+		call,
+		AccountId::from(signer.public()).into(),
+		runtime::Signature::Sr25519(signature),
+		extra,
+	)
+}
+
+fn string(bytes: &[u8]) -> String {
+	String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn hex(bytes: &[u8]) -> String {
+	format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+fn graphql_id(local_id: u32) -> ID {
+	ID(local_id.to_string())
+}
+
+fn local_id(id: &ID) -> Result<u32> {
+	id.parse().map_err(|_| Error::new(format!("Invalid id {}", id.as_str())))
+}
+
+fn bounded<S: Get<u32>>(field: &str, text: String) -> Result<BoundedVec<u8, S>> {
+	text.into_bytes()
+		.try_into()
+		.map_err(|_| Error::new(format!("{} is too long", field)))
+}
+
+/// Push a transaction noting `text` to `calls`, returning the hash to reference it by.
+fn noted(calls: &mut Vec<RuntimeCall>, text: Option<String>) -> Option<H256> {
+	let bytes = text?.into_bytes();
+	let hash = H256(blake2_256(&bytes));
+	calls.push(PreimageCall::<Runtime>::note_preimage { bytes }.into());
+	Some(hash)
+}
+
+/// A registered agent.
+#[derive(SimpleObject)]
+struct Agent {
+	id: ID,
+}
+
+/// A unit of measure.
+#[derive(SimpleObject)]
+struct Unit {
+	id: ID,
+	label: String,
+	symbol: String,
+}
+
+impl Unit {
+	fn new(id: UnitId, unit: agent::Unit<Runtime>) -> Self {
+		Self { id: graphql_id(id.0), label: string(&unit.label), symbol: string(&unit.symbol) }
+	}
+}
+
+/// A mappable place.
+#[derive(SimpleObject)]
+struct SpatialThing {
+	id: ID,
+	name: String,
+	note: Option<String>,
+	mappable_address: Option<String>,
+	lat: Option<f64>,
+	long: Option<f64>,
+	alt: Option<f64>,
+}
+
+/// A type of process.
+#[derive(SimpleObject)]
+struct ProcessSpecification {
+	id: ID,
+	name: String,
+	note: Option<String>,
+	/// Number of times the specification was updated or archived.
+	revision: u32,
+	/// Block the specification was archived in.
+	archived_at: Option<u32>,
+}
+
+/// A type of economic resource.
+#[derive(SimpleObject)]
+struct ResourceSpecification {
+	id: ID,
+	name: String,
+	note: Option<String>,
+	/// CID of the first image.
+	image: Option<String>,
+	/// CIDs of all the images.
+	image_list: Vec<String>,
+	/// Ids of the classifying concepts.
+	resource_classified_as: Vec<ID>,
+	default_unit_of_resource: Option<Unit>,
+	default_unit_of_effort: Option<Unit>,
+	/// Number of times the specification was updated or archived.
+	revision: u32,
+	/// Block the specification was archived in.
+	archived_at: Option<u32>,
+}
+
+/// Queries of the schema. The plural queries return Relay connections, with records listed by id
+/// and agents by address.
+pub(crate) struct Query;
+
+#[Object]
+impl Query {
+	async fn agent(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Agent>> {
+		let account =
+			AccountId::from_ss58check(&id).map_err(|_| Error::new("Invalid agent address"))?;
+		let registered: Option<bool> =
+			State::get(ctx)
+				.records()
+				.storage
+				.value("Agents", Hasher::Blake2_128Concat, account)?;
+		Ok(registered.map(|_| Agent { id }))
+	}
+
+	async fn agents(
+		&self,
+		ctx: &Context<'_>,
+		after: Option<String>,
+		before: Option<String>,
+		first: Option<i32>,
+		last: Option<i32>,
+	) -> Result<Connection<String, Agent>> {
+		let agents = State::get(ctx)
+			.records()
+			.storage
+			.entries::<AccountId, bool>("Agents", Hasher::Blake2_128Concat)?
+			.into_iter()
+			.map(|(account, _)| {
+				let address = account.to_ss58check();
+				(address.clone(), Agent { id: ID(address) })
+			})
+			.collect();
+		connection(agents, after, before, first, last).await
+	}
+
+	async fn unit(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Unit>> {
+		State::get(ctx).records().unit(UnitId(local_id(&id)?))
+	}
+
+	async fn units(
+		&self,
+		ctx: &Context<'_>,
+		after: Option<String>,
+		before: Option<String>,
+		first: Option<i32>,
+		last: Option<i32>,
+	) -> Result<Connection<usize, Unit>> {
+		let units = State::get(ctx)
+			.records()
+			.storage
+			.entries::<UnitId, _>("Units", Hasher::Twox64Concat)?
+			.into_iter()
+			.map(|(id, unit)| (id.0 as usize, Unit::new(id, unit)))
+			.collect();
+		connection(units, after, before, first, last).await
+	}
+
+	async fn spatial_thing(&self, ctx: &Context<'_>, id: ID) -> Result<Option<SpatialThing>> {
+		let id = agent::SpatialThingId(local_id(&id)?);
+		State::get(ctx).records().get("SpatialThings", id, Records::spatial_thing)
+	}
+
+	async fn spatial_things(
+		&self,
+		ctx: &Context<'_>,
+		after: Option<String>,
+		before: Option<String>,
+		first: Option<i32>,
+		last: Option<i32>,
+	) -> Result<Connection<usize, SpatialThing>> {
+		let records = State::get(ctx).records();
+		let spatial_things = records
+			.storage
+			.entries::<agent::SpatialThingId, _>("SpatialThings", Hasher::Twox64Concat)?
+			.into_iter()
+			.map(|(id, spatial_thing)| {
+				Ok((id.0 as usize, records.spatial_thing(id, spatial_thing)?))
+			})
+			.collect::<Result<_>>()?;
+		connection(spatial_things, after, before, first, last).await
+	}
+
+	async fn process_specification(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+	) -> Result<Option<ProcessSpecification>> {
+		let id = agent::ProcessSpecificationId(local_id(&id)?);
+		State::get(ctx)
+			.records()
+			.get("ProcessSpecifications", id, Records::process_specification)
+	}
+
+	/// The process specifications, leaving out archived ones unless `include_archived` is set.
+	async fn process_specifications(
+		&self,
+		ctx: &Context<'_>,
+		#[graphql(default)] include_archived: bool,
+		after: Option<String>,
+		before: Option<String>,
+		first: Option<i32>,
+		last: Option<i32>,
+	) -> Result<Connection<usize, ProcessSpecification>> {
+		let records = State::get(ctx).records();
+		let process_specs = records
+			.storage
+			.entries::<agent::ProcessSpecificationId, agent::ProcessSpecification<Runtime>>(
+				"ProcessSpecifications",
+				Hasher::Twox64Concat,
+			)?
+			.into_iter()
+			.filter(|(_, process_spec)| include_archived || process_spec.archived_at.is_none())
+			.map(|(id, process_spec)| {
+				Ok((id.0 as usize, records.process_specification(id, process_spec)?))
+			})
+			.collect::<Result<_>>()?;
+		connection(process_specs, after, before, first, last).await
+	}
+
+	async fn resource_specification(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+	) -> Result<Option<ResourceSpecification>> {
+		let id = agent::ResourceSpecificationId(local_id(&id)?);
+		State::get(ctx)
+			.records()
+			.get("ResourceSpecifications", id, Records::resource_specification)
+	}
+
+	/// The resource specifications, leaving out archived ones unless `include_archived` is set.
+	async fn resource_specifications(
+		&self,
+		ctx: &Context<'_>,
+		#[graphql(default)] include_archived: bool,
+		after: Option<String>,
+		before: Option<String>,
+		first: Option<i32>,
+		last: Option<i32>,
+	) -> Result<Connection<usize, ResourceSpecification>> {
+		let records = State::get(ctx).records();
+		let resource_specs = records
+			.storage
+			.entries::<agent::ResourceSpecificationId, agent::ResourceSpecification<Runtime>>(
+				"ResourceSpecifications",
+				Hasher::Twox64Concat,
+			)?
+			.into_iter()
+			.filter(|(_, resource_spec)| include_archived || resource_spec.archived_at.is_none())
+			.map(|(id, resource_spec)| {
+				Ok((id.0 as usize, records.resource_specification(id, resource_spec)?))
+			})
+			.collect::<Result<_>>()?;
+		connection(resource_specs, after, before, first, last).await
+	}
+}
+
+#[derive(InputObject)]
+struct UnitCreateParams {
+	label: String,
+	symbol: String,
+}
+
+#[derive(InputObject)]
+struct SpatialThingCreateParams {
+	name: String,
+	note: Option<String>,
+	mappable_address: Option<String>,
+	lat: Option<f64>,
+	long: Option<f64>,
+	alt: Option<f64>,
+}
+
+#[derive(InputObject)]
+struct ProcessSpecificationCreateParams {
+	name: String,
+	note: Option<String>,
+}
+
+#[derive(InputObject)]
+struct ResourceSpecificationCreateParams {
+	name: String,
+	note: Option<String>,
+	#[graphql(default)]
+	resource_classified_as: Vec<ID>,
+	default_unit_of_resource: Option<ID>,
+	default_unit_of_effort: Option<ID>,
+}
+
+/// Response of `createUnit`.
+#[derive(SimpleObject)]
+struct UnitResponse {
+	unit: Unit,
+}
+
+/// Response of `createSpatialThing`.
+#[derive(SimpleObject)]
+struct SpatialThingResponse {
+	spatial_thing: SpatialThing,
+}
+
+/// Response of `createProcessSpecification`.
+#[derive(SimpleObject)]
+struct ProcessSpecificationResponse {
+	process_specification: ProcessSpecification,
+}
+
+/// Response of `createResourceSpecification`.
+#[derive(SimpleObject)]
+struct ResourceSpecificationResponse {
+	resource_specification: ResourceSpecification,
+}
+
+/// Submit `calls` and read the record the last one created from the block including it, with
+/// `id` picking its id from the events and `read` reading it.
+async fn create<I, N>(
+	ctx: &Context<'_>,
+	calls: Vec<RuntimeCall>,
+	id: impl Fn(&agent::Event<Runtime>) -> Option<I>,
+	read: impl FnOnce(&Records<'_>, I) -> Result<Option<N>>,
+) -> Result<N> {
+	let (block, events) = State::submit(ctx, calls).await?;
+	let id = events
+		.iter()
+		.find_map(|event| match event {
+			RuntimeEvent::ValueflowsAgent(event) => id(event),
+			_ => None,
+		})
+		.ok_or_else(|| Error::new("The transaction created no record"))?;
+	read(&State::get(ctx).records_at(block), id)?
+		.ok_or_else(|| Error::new("The created record is missing from its block"))
+}
+
+/// Mutations of the schema, submitted as extrinsics signed by the node's GraphQL signer. They
+/// return once the extrinsics are included in a block.
+pub(crate) struct Mutation;
+
+#[Object]
+impl Mutation {
+	async fn create_unit(&self, ctx: &Context<'_>, unit: UnitCreateParams) -> Result<UnitResponse> {
+		let call = agent::Call::<Runtime>::create_unit {
+			label: bounded("label", unit.label)?,
+			symbol: bounded("symbol", unit.symbol)?,
+		};
+		let unit = create(
+			ctx,
+			vec![call.into()],
+			|event| match event {
+				agent::Event::UnitCreated(id, _) => Some(*id),
+				_ => None,
+			},
+			|records, id| records.unit(id),
+		)
+		.await?;
+		Ok(UnitResponse { unit })
+	}
+
+	async fn create_spatial_thing(
+		&self,
+		ctx: &Context<'_>,
+		spatial_thing: SpatialThingCreateParams,
+	) -> Result<SpatialThingResponse> {
+		let mut calls = Vec::new();
+		let call = agent::Call::<Runtime>::create_spatial_thing {
+			name: bounded("name", spatial_thing.name)?,
+			note: noted(&mut calls, spatial_thing.note),
+			mappable_address: noted(&mut calls, spatial_thing.mappable_address),
+			lat: spatial_thing.lat.map(FixedI64::from_float),
+			long: spatial_thing.long.map(FixedI64::from_float),
+			alt: spatial_thing.alt.map(FixedI64::from_float),
+		};
+		calls.push(call.into());
+		let spatial_thing = create(
+			ctx,
+			calls,
+			|event| match event {
+				agent::Event::SpatialThingCreated(id, _) => Some(*id),
+				_ => None,
+			},
+			|records, id| records.get("SpatialThings", id, Records::spatial_thing),
+		)
+		.await?;
+		Ok(SpatialThingResponse { spatial_thing })
+	}
+
+	async fn create_process_specification(
+		&self,
+		ctx: &Context<'_>,
+		process_specification: ProcessSpecificationCreateParams,
+	) -> Result<ProcessSpecificationResponse> {
+		let mut calls = Vec::new();
+		let call = agent::Call::<Runtime>::create_process_specification {
+			name: bounded("name", process_specification.name)?,
+			note: noted(&mut calls, process_specification.note),
+		};
+		calls.push(call.into());
+		let process_specification = create(
+			ctx,
+			calls,
+			|event| match event {
+				agent::Event::ProcessSpecificationCreated(id, _) => Some(*id),
+				_ => None,
+			},
+			|records, id| records.get("ProcessSpecifications", id, Records::process_specification),
+		)
+		.await?;
+		Ok(ProcessSpecificationResponse { process_specification })
+	}
+
+	async fn create_resource_specification(
+		&self,
+		ctx: &Context<'_>,
+		resource_specification: ResourceSpecificationCreateParams,
+	) -> Result<ResourceSpecificationResponse> {
+		let unit_id =
+			|id: Option<ID>| -> Result<_> { id.map(|id| local_id(&id).map(UnitId)).transpose() };
+		let resource_classified_as = resource_specification
+			.resource_classified_as
+			.iter()
+			.map(|id| local_id(id).map(ConceptId))
+			.collect::<Result<Vec<_>>>()?
+			.try_into()
+			.map_err(|_| Error::new("Too many classifications"))?;
+
+		let mut calls = Vec::new();
+		let call = agent::Call::<Runtime>::create_resource_specification {
+			name: bounded("name", resource_specification.name)?,
+			images: Default::default(),
+			note: noted(&mut calls, resource_specification.note),
+			resource_classified_as,
+			default_unit_of_resource_id: unit_id(resource_specification.default_unit_of_resource)?,
+			default_unit_of_effort_id: unit_id(resource_specification.default_unit_of_effort)?,
+		};
+		calls.push(call.into());
+		let resource_specification = create(
+			ctx,
+			calls,
+			|event| match event {
+				agent::Event::ResourceSpecificationCreated(id, _) => Some(*id),
+				_ => None,
+			},
+			|records, id| {
+				records.get("ResourceSpecifications", id, Records::resource_specification)
+			},
+		)
+		.await?;
+		Ok(ResourceSpecificationResponse { resource_specification })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::executor::block_on;
+
+	#[test]
+	fn next_nonce_follows_the_ready_transactions_of_the_account() {
+		let alice = AccountId::from([1; 32]);
+		let bob = AccountId::from([2; 32]);
+		let tags = vec![(&alice, 5u32).encode(), (&bob, 6u32).encode(), (&alice, 6u32).encode()];
+
+		assert_eq!(next_nonce(5, &alice, tags.clone().into_iter()), 7);
+		assert_eq!(next_nonce(4, &alice, tags.clone().into_iter()), 4);
+		assert_eq!(next_nonce(0, &bob, tags.into_iter()), 0);
+	}
+
+	fn page(
+		after: Option<usize>,
+		before: Option<usize>,
+		first: Option<i32>,
+		last: Option<i32>,
+	) -> (Vec<usize>, bool, bool) {
+		let nodes = [5usize, 1, 3, 2].into_iter().map(|id| (id, id.to_string())).collect();
+		let cursor = |c: Option<usize>| c.map(|c| c.encode_cursor());
+		let connection =
+			block_on(connection(nodes, cursor(after), cursor(before), first, last)).unwrap();
+		let cursors = connection.edges.iter().map(|edge| edge.cursor).collect();
+		(cursors, connection.has_previous_page, connection.has_next_page)
+	}
+
+	#[test]
+	fn connection_pages_by_cursor() {
+		assert_eq!(page(None, None, None, None), (vec![1, 2, 3, 5], false, false));
+		assert_eq!(page(Some(1), None, Some(2), None), (vec![2, 3], true, true));
+		assert_eq!(page(None, Some(5), None, Some(1)), (vec![3], true, true));
+		assert_eq!(page(Some(3), None, Some(2), None), (vec![5], true, false));
+	}
+
+	fn access() -> Access {
+		Access {
+			token: Some("secret".to_owned()),
+			allowed_origins: vec!["http://localhost:3000".to_owned()],
+		}
+	}
+
+	fn post(headers: &[(&str, &str)]) -> Request<Body> {
+		let mut request = Request::post("/");
+		for (name, value) in headers {
+			request = request.header(*name, *value);
+		}
+		request.body(Body::empty()).unwrap()
+	}
+
+	#[test]
+	fn check_refuses_other_media_types_and_origins() {
+		assert_eq!(check(&access(), &post(&[])), Err(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+		assert_eq!(
+			check(&access(), &post(&[("Content-Type", "text/plain")])),
+			Err(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+		);
+		assert_eq!(
+			check(
+				&access(),
+				&post(&[("Content-Type", "application/json"), ("Origin", "https://evil.example")])
+			),
+			Err(StatusCode::FORBIDDEN)
+		);
+		assert_eq!(
+			check(
+				&access(),
+				&post(&[
+					("Content-Type", "application/json; charset=utf-8"),
+					("Origin", "http://localhost:3000")
+				])
+			),
+			Ok(false)
+		);
+	}
+
+	#[test]
+	fn check_authorizes_requests_with_the_bearer_token() {
+		let json = ("Content-Type", "application/json");
+		assert_eq!(check(&access(), &post(&[json])), Ok(false));
+		assert_eq!(check(&access(), &post(&[json, ("Authorization", "Bearer guess")])), Ok(false));
+		assert_eq!(check(&access(), &post(&[json, ("Authorization", "Bearer secret")])), Ok(true));
+
+		let without_token = Access { token: None, ..access() };
+		assert_eq!(check(&without_token, &post(&[json, ("Authorization", "Bearer ")])), Ok(false));
+	}
+
+	#[test]
+	fn noted_submits_the_text_as_a_preimage() {
+		let mut calls = Vec::new();
+		assert_eq!(noted(&mut calls, None), None);
+		assert!(calls.is_empty());
+
+		let hash = noted(&mut calls, Some("ripe".to_owned()));
+		assert_eq!(hash, Some(H256(blake2_256(b"ripe"))));
+		let note: RuntimeCall =
+			PreimageCall::<Runtime>::note_preimage { bytes: b"ripe".to_vec() }.into();
+		assert_eq!(calls, vec![note]);
+	}
+
+	#[test]
+	fn schema_uses_vf_graphql_names() {
+		let sdl = Schema::build(Query, Mutation, EmptySubscription).finish().sdl();
+
+		for field in [
+			"includeArchived: Boolean! = false",
+			"): ResourceSpecificationConnection!",
+			"edges: [ResourceSpecificationEdge!]!",
+			"pageInfo: PageInfo!",
+			"mappableAddress: String",
+			"imageList: [String!]!",
+			"resourceClassifiedAs: [ID!]!",
+			"defaultUnitOfResource: Unit",
+			"defaultUnitOfEffort: Unit",
+			"createUnit(unit: UnitCreateParams!): UnitResponse!",
+			"createResourceSpecification(resourceSpecification: \
+			 ResourceSpecificationCreateParams!): ResourceSpecificationResponse!",
+			"resourceSpecification: ResourceSpecification!",
+		] {
+			assert!(sdl.contains(field), "{} is missing from the schema", field);
+		}
+	}
+}
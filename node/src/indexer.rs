@@ -0,0 +1,892 @@
+//! Off-chain index of the agent pallet records, kept in an embedded SQLite database.
+//!
+//! The indexer follows the storage changes of imported best blocks and mirrors every unit, spatial
+//! thing, specification and concept into a `records` table, together with the block and time it
+//! was created, last updated and, for specifications, archived. Storage changes are used rather
+//! than events because updates and deletions of most records emit no event. On startup the index
+//! is reconciled with the state of the best block, so records changed while the node was down are
+//! picked up too. The same happens for a best block that does not build on the last indexed one,
+//! after a reorg, so the changes of retracted blocks are undone. Archived specifications are left
+//! out of queries unless asked for.
+//!
+//! The index is served through the `vf_queryRecords` RPC method, which supports the filtering,
+//! sorting and paging that raw storage iteration can't offer.
+
+use std::{
+	collections::BTreeSet,
+	path::Path,
+	sync::{Arc, Mutex},
+};
+
+use codec::Decode;
+use futures::StreamExt;
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::{
+	opaque::Block,
+	pallet_valueflows_agent::{
+		Concept, ConceptScheme, ProcessSpecification, ResourceSpecification, SpatialThing, Unit,
+	},
+	AccountId, Runtime,
+};
+use rusqlite::{
+	params, params_from_iter,
+	types::{FromSql, FromSqlError, FromSqlResult, ValueRef},
+	Connection, ToSql,
+};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::{HeaderBackend, HeaderMetadata};
+use sp_core::{
+	crypto::Ss58Codec,
+	hashing::twox_128,
+	storage::{StorageData, StorageKey},
+};
+use sp_runtime::traits::Block as BlockT;
+
+/// Default number of records returned by a query.
+const DEFAULT_LIMIT: u32 = 100;
+/// Largest number of records returned by a query.
+const MAX_LIMIT: u32 = 1_000;
+
+/// Length of the `Twox64Concat` hash preceding the ids in record storage keys.
+const TWOX64_LENGTH: usize = 8;
+
+const SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS records (
+		kind TEXT NOT NULL,
+		id INTEGER NOT NULL,
+		owner TEXT NOT NULL,
+		name TEXT NOT NULL,
+		broader INTEGER,
+		created_block INTEGER NOT NULL,
+		created_at INTEGER,
+		updated_block INTEGER NOT NULL,
+		updated_at INTEGER,
+		archived_block INTEGER,
+		PRIMARY KEY (kind, id)
+	);
+	CREATE INDEX IF NOT EXISTS records_owner ON records (owner);
+	CREATE INDEX IF NOT EXISTS records_created_at ON records (created_at);
+	CREATE TABLE IF NOT EXISTS classifications (
+		resource_specification_id INTEGER NOT NULL,
+		concept_id INTEGER NOT NULL,
+		PRIMARY KEY (resource_specification_id, concept_id)
+	);
+	CREATE INDEX IF NOT EXISTS classifications_concept ON classifications (concept_id);
+";
+
+/// The type of a record kept by the agent pallet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Kind {
+	/// A unit of measure.
+	Unit,
+	/// A place.
+	SpatialThing,
+	/// A process specification.
+	ProcessSpecification,
+	/// A resource specification.
+	ResourceSpecification,
+	/// A taxonomy of concepts.
+	ConceptScheme,
+	/// A concept classifying resource specifications.
+	Concept,
+}
+
+impl Kind {
+	const ALL: [Kind; 6] = [
+		Kind::Unit,
+		Kind::SpatialThing,
+		Kind::ProcessSpecification,
+		Kind::ResourceSpecification,
+		Kind::ConceptScheme,
+		Kind::Concept,
+	];
+
+	/// Name of the storage map holding records of this kind.
+	fn storage_item(self) -> &'static str {
+		match self {
+			Kind::Unit => "Units",
+			Kind::SpatialThing => "SpatialThings",
+			Kind::ProcessSpecification => "ProcessSpecifications",
+			Kind::ResourceSpecification => "ResourceSpecifications",
+			Kind::ConceptScheme => "ConceptSchemes",
+			Kind::Concept => "Concepts",
+		}
+	}
+
+	/// Value of the `kind` column.
+	fn as_str(self) -> &'static str {
+		match self {
+			Kind::Unit => "unit",
+			Kind::SpatialThing => "spatial_thing",
+			Kind::ProcessSpecification => "process_specification",
+			Kind::ResourceSpecification => "resource_specification",
+			Kind::ConceptScheme => "concept_scheme",
+			Kind::Concept => "concept",
+		}
+	}
+
+	fn parse(kind: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|k| k.as_str() == kind)
+	}
+
+	fn storage_prefix(self) -> Vec<u8> {
+		storage_prefix("ValueflowsAgent", self.storage_item())
+	}
+
+	/// Decode the fields of a stored record that are kept in the index.
+	fn decode(self, value: &[u8]) -> Option<Fields> {
+		let input = &mut &value[..];
+		let fields = match self {
+			Kind::Unit => {
+				let unit = Unit::<Runtime>::decode(input).ok()?;
+				Fields::new(unit.owner, &unit.label)
+			},
+			Kind::SpatialThing => {
+				let spatial_thing = SpatialThing::<Runtime>::decode(input).ok()?;
+				Fields::new(spatial_thing.owner, &spatial_thing.name)
+			},
+			Kind::ProcessSpecification => {
+				let process_spec = ProcessSpecification::<Runtime>::decode(input).ok()?;
+				Fields {
+					archived_block: process_spec.archived_at,
+					..Fields::new(process_spec.owner, &process_spec.name)
+				}
+			},
+			Kind::ResourceSpecification => {
+				let resource_spec = ResourceSpecification::<Runtime>::decode(input).ok()?;
+				Fields {
+					classified_as: resource_spec
+						.resource_classified_as
+						.iter()
+						.map(|concept_id| concept_id.0)
+						.collect(),
+					archived_block: resource_spec.archived_at,
+					..Fields::new(resource_spec.owner, &resource_spec.name)
+				}
+			},
+			Kind::ConceptScheme => {
+				let scheme = ConceptScheme::<Runtime>::decode(input).ok()?;
+				Fields::new(scheme.owner, &scheme.name)
+			},
+			Kind::Concept => {
+				let concept = Concept::<Runtime>::decode(input).ok()?;
+				Fields {
+					broader: concept.broader.map(|concept_id| concept_id.0),
+					..Fields::new(concept.owner, &concept.label)
+				}
+			},
+		};
+		Some(fields)
+	}
+}
+
+impl FromSql for Kind {
+	fn column_result(value: ValueRef) -> FromSqlResult<Self> {
+		let kind = value.as_str()?;
+		Kind::parse(kind)
+			.ok_or_else(|| FromSqlError::Other(format!("Unknown record kind {}", kind).into()))
+	}
+}
+
+/// Error updating the index.
+#[derive(Debug)]
+pub enum Error {
+	/// The database query failed.
+	Sql(rusqlite::Error),
+	/// A stored record could not be decoded, e.g. after a runtime upgrade changed its encoding.
+	Decode(Kind, u32),
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Error::Sql(e) => write!(f, "Database error: {}", e),
+			Error::Decode(kind, id) => write!(f, "Failed to decode {} {}", kind.as_str(), id),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<rusqlite::Error> for Error {
+	fn from(e: rusqlite::Error) -> Self {
+		Error::Sql(e)
+	}
+}
+
+/// The indexed fields of a record.
+struct Fields {
+	owner: String,
+	name: String,
+	broader: Option<u32>,
+	classified_as: Vec<u32>,
+	archived_block: Option<u32>,
+}
+
+impl Fields {
+	fn new(owner: AccountId, name: &[u8]) -> Self {
+		Self {
+			owner: owner.to_ss58check(),
+			name: String::from_utf8_lossy(name).into_owned(),
+			broader: None,
+			classified_as: Vec::new(),
+			archived_block: None,
+		}
+	}
+}
+
+/// The prefix of the storage keys of `item` in `pallet`.
+pub(crate) fn storage_prefix(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// The block a change was made in.
+#[derive(Clone, Copy)]
+struct At {
+	number: u32,
+	/// Milliseconds since the unix epoch, when the timestamp was set.
+	timestamp: Option<u64>,
+}
+
+/// Column records are sorted by.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderBy {
+	/// Record id.
+	#[default]
+	Id,
+	/// Record name, or label for units and concepts.
+	Name,
+	/// Creation time.
+	CreatedAt,
+	/// Time of the last update.
+	UpdatedAt,
+}
+
+impl OrderBy {
+	fn column(self) -> &'static str {
+		match self {
+			OrderBy::Id => "id",
+			OrderBy::Name => "name",
+			OrderBy::CreatedAt => "created_block",
+			OrderBy::UpdatedAt => "updated_block",
+		}
+	}
+}
+
+/// Filters, order and page of a records query. Every filter is optional.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordQuery {
+	/// Only records of this kind.
+	pub kind: Option<Kind>,
+	/// Only records owned by this agent.
+	pub owner: Option<AccountId>,
+	/// Only resource specifications classified under this concept or its narrower concepts.
+	pub classified_as: Option<u32>,
+	/// Only records created at or after this time, in milliseconds since the unix epoch.
+	pub created_after: Option<u64>,
+	/// Only records created before this time, in milliseconds since the unix epoch.
+	pub created_before: Option<u64>,
+	/// Also return archived specifications, which are left out by default.
+	pub include_archived: bool,
+	/// Column to sort by, ties are broken by kind and id.
+	pub order_by: OrderBy,
+	/// Sort in descending order.
+	pub descending: bool,
+	/// Number of records to return, at most 1000.
+	pub limit: Option<u32>,
+	/// Number of matching records to skip.
+	pub offset: Option<u32>,
+}
+
+/// A record as kept in the index.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedRecord {
+	pub kind: Kind,
+	pub id: u32,
+	/// SS58 address of the owner.
+	pub owner: String,
+	pub name: String,
+	/// The broader concept of a concept.
+	pub broader: Option<u32>,
+	/// The concepts classifying a resource specification.
+	pub classified_as: Vec<u32>,
+	pub created_block: u32,
+	pub created_at: Option<u64>,
+	pub updated_block: u32,
+	pub updated_at: Option<u64>,
+	/// The block a specification was archived in.
+	pub archived_block: Option<u32>,
+}
+
+/// The SQLite database holding the index.
+pub struct Index {
+	conn: Mutex<Connection>,
+}
+
+impl Index {
+	/// Open the index at `path`, creating the database if needed.
+	pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+		Self::new(Connection::open(path)?)
+	}
+
+	/// Use the database of `conn` as the index, creating the tables if needed.
+	fn new(conn: Connection) -> rusqlite::Result<Self> {
+		conn.execute_batch(SCHEMA)?;
+		// Indexes created before specifications could be archived lack the column.
+		let archivable: bool = conn.query_row(
+			"SELECT COUNT(*) > 0 FROM pragma_table_info('records') WHERE name = 'archived_block'",
+			[],
+			|row| row.get(0),
+		)?;
+		if !archivable {
+			conn.execute_batch("ALTER TABLE records ADD COLUMN archived_block INTEGER")?;
+		}
+		Ok(Self { conn: Mutex::new(conn) })
+	}
+
+	/// Insert, update or, when `value` is `None`, remove a record.
+	fn apply(
+		conn: &Connection,
+		kind: Kind,
+		id: u32,
+		value: Option<&[u8]>,
+		at: At,
+	) -> Result<(), Error> {
+		let Some(value) = value else {
+			conn.execute(
+				"DELETE FROM records WHERE kind = ?1 AND id = ?2",
+				params![kind.as_str(), id],
+			)?;
+			if kind == Kind::ResourceSpecification {
+				Self::classify(conn, id, &[])?;
+			}
+			return Ok(())
+		};
+		let fields = kind.decode(value).ok_or(Error::Decode(kind, id))?;
+
+		conn.execute(
+			"INSERT INTO records (kind, id, owner, name, broader, created_block, created_at,
+				updated_block, updated_at, archived_block)
+			VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?6, ?7, ?8)
+			ON CONFLICT (kind, id) DO UPDATE SET owner = ?3, name = ?4, broader = ?5,
+				updated_block = ?6, updated_at = ?7, archived_block = ?8",
+			params![
+				kind.as_str(),
+				id,
+				fields.owner,
+				fields.name,
+				fields.broader,
+				at.number,
+				at.timestamp,
+				fields.archived_block
+			],
+		)?;
+		if kind == Kind::ResourceSpecification {
+			Self::classify(conn, id, &fields.classified_as)?;
+		}
+		Ok(())
+	}
+
+	/// Replace the concepts classifying a resource specification.
+	fn classify(conn: &Connection, id: u32, concept_ids: &[u32]) -> rusqlite::Result<()> {
+		conn.execute(
+			"DELETE FROM classifications WHERE resource_specification_id = ?1",
+			params![id],
+		)?;
+		for concept_id in concept_ids {
+			conn.execute(
+				"INSERT OR IGNORE INTO classifications VALUES (?1, ?2)",
+				params![id, concept_id],
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Apply the changes made to the agent pallet storage in a block. Nothing is applied when
+	/// any of them fails.
+	fn apply_changes<'a>(
+		&self,
+		changes: impl Iterator<Item = (&'a StorageKey, Option<&'a StorageData>)>,
+		at: At,
+	) -> Result<(), Error> {
+		let prefixes: Vec<_> = Kind::ALL.into_iter().map(|k| (k, k.storage_prefix())).collect();
+		let mut conn = self.conn.lock().expect("index lock is never poisoned; qed");
+		let tx = conn.transaction()?;
+		for (key, value) in changes {
+			let Some((kind, id)) = prefixes.iter().find_map(|(kind, prefix)| {
+				let id = key.0.strip_prefix(&prefix[..])?.get(TWOX64_LENGTH..)?;
+				Some((*kind, u32::decode(&mut &id[..]).ok()?))
+			}) else {
+				continue
+			};
+			Self::apply(&tx, kind, id, value.map(|v| &v.0[..]), at)?;
+		}
+		Ok(tx.commit()?)
+	}
+
+	/// Make the index match the full state of a block, removing records deleted since.
+	fn reconcile(&self, records: &[(Kind, Vec<(u32, StorageData)>)], at: At) -> Result<(), Error> {
+		let mut conn = self.conn.lock().expect("index lock is never poisoned; qed");
+		let tx = conn.transaction()?;
+		for (kind, records) in records {
+			let indexed: BTreeSet<u32> = tx
+				.prepare("SELECT id FROM records WHERE kind = ?1")?
+				.query_map(params![kind.as_str()], |row| row.get(0))?
+				.collect::<Result<_, _>>()?;
+			let existing: BTreeSet<u32> = records.iter().map(|(id, _)| *id).collect();
+			for id in indexed.difference(&existing) {
+				Self::apply(&tx, *kind, *id, None, at)?;
+			}
+			for (id, value) in records {
+				// Records already indexed keep their times, as it's unknown when they changed.
+				if !indexed.contains(id) {
+					Self::apply(&tx, *kind, *id, Some(&value.0), at)?;
+				} else {
+					let fields = kind.decode(&value.0).ok_or(Error::Decode(*kind, *id))?;
+					tx.execute(
+						"UPDATE records SET owner = ?3, name = ?4, broader = ?5, archived_block = ?6
+						WHERE kind = ?1 AND id = ?2",
+						params![
+							kind.as_str(),
+							id,
+							fields.owner,
+							fields.name,
+							fields.broader,
+							fields.archived_block
+						],
+					)?;
+					Self::classify(&tx, *id, &fields.classified_as)?;
+				}
+			}
+		}
+		Ok(tx.commit()?)
+	}
+
+	/// Find the records matching `query`.
+	pub fn query(&self, query: &RecordQuery) -> rusqlite::Result<Vec<IndexedRecord>> {
+		let mut clauses = Vec::new();
+		let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+		let mut filter = |clause: &str, value: Box<dyn ToSql>| {
+			values.push(value);
+			clauses.push(clause.replace('?', &format!("?{}", values.len())));
+		};
+		if let Some(kind) = query.kind {
+			filter("kind = ?", Box::new(kind.as_str()));
+		}
+		if let Some(owner) = &query.owner {
+			filter("owner = ?", Box::new(owner.to_ss58check()));
+		}
+		if let Some(concept_id) = query.classified_as {
+			filter(
+				"kind = 'resource_specification' AND id IN (
+					WITH RECURSIVE narrower(id) AS (
+						SELECT ?
+						UNION
+						SELECT records.id FROM records JOIN narrower ON records.broader = narrower.id
+						WHERE records.kind = 'concept'
+					)
+					SELECT resource_specification_id FROM classifications
+					WHERE concept_id IN narrower
+				)",
+				Box::new(concept_id),
+			);
+		}
+		if let Some(after) = query.created_after {
+			filter("created_at >= ?", Box::new(after));
+		}
+		if let Some(before) = query.created_before {
+			filter("created_at < ?", Box::new(before));
+		}
+		if !query.include_archived {
+			clauses.push("archived_block IS NULL".to_owned());
+		}
+
+		let sql = format!(
+			"SELECT kind, id, owner, name, broader, created_block, created_at, updated_block,
+				updated_at, archived_block
+			FROM records {} ORDER BY {} {}, kind, id LIMIT {} OFFSET {}",
+			if clauses.is_empty() {
+				String::new()
+			} else {
+				format!("WHERE {}", clauses.join(" AND "))
+			},
+			query.order_by.column(),
+			if query.descending { "DESC" } else { "ASC" },
+			query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT),
+			query.offset.unwrap_or_default(),
+		);
+
+		let conn = self.conn.lock().expect("index lock is never poisoned; qed");
+		let mut records = conn
+			.prepare(&sql)?
+			.query_map(params_from_iter(values.iter()), |row| {
+				Ok(IndexedRecord {
+					kind: row.get(0)?,
+					id: row.get(1)?,
+					owner: row.get(2)?,
+					name: row.get(3)?,
+					broader: row.get(4)?,
+					classified_as: Vec::new(),
+					created_block: row.get(5)?,
+					created_at: row.get(6)?,
+					updated_block: row.get(7)?,
+					updated_at: row.get(8)?,
+					archived_block: row.get(9)?,
+				})
+			})?
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let mut classified_as = conn.prepare(
+			"SELECT concept_id FROM classifications WHERE resource_specification_id = ?1
+			ORDER BY concept_id",
+		)?;
+		for record in records.iter_mut().filter(|r| r.kind == Kind::ResourceSpecification) {
+			record.classified_as = classified_as
+				.query_map(params![record.id], |row| row.get(0))?
+				.collect::<Result<_, _>>()?;
+		}
+		Ok(records)
+	}
+}
+
+/// Keep `index` in sync with the best chain of `client`.
+pub async fn run<C>(client: Arc<C>, index: Arc<Index>)
+where
+	C: BlockchainEvents<Block> + StorageProvider<Block, sc_service::TFullBackend<Block>>,
+	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	C: Send + Sync + 'static,
+{
+	// Subscribe before reconciling, so no block is missed in between.
+	let mut changes = match client.storage_changes_notification_stream(None, None) {
+		Ok(changes) => changes,
+		Err(e) => {
+			log::error!("Indexer failed to subscribe to storage changes: {}", e);
+			return
+		},
+	};
+
+	let best_hash = client.info().best_hash;
+	let mut last_indexed = match reconcile(&*client, &index, best_hash) {
+		Ok(()) => Some(best_hash),
+		Err(e) => {
+			log::error!("Indexer failed to reconcile with the best block: {}", e);
+			None
+		},
+	};
+
+	let timestamp_key = storage_prefix("Timestamp", "Now");
+	while let Some(notification) = changes.next().await {
+		let hash = notification.block;
+		let Ok(Some(number)) = client.number(hash) else { continue };
+
+		// The changes of a block are relative to its parent. When the parent is not the last
+		// indexed block, after a reorg or a block that failed to be indexed, the index is
+		// reconciled with the full state of the block instead.
+		let parent = client.header_metadata(hash).ok().map(|header| header.parent);
+		if parent.is_none() || parent != last_indexed {
+			last_indexed = match reconcile(&*client, &index, hash) {
+				Ok(()) => Some(hash),
+				Err(e) => {
+					log::error!("Indexer failed to reconcile with block #{}: {}", number, e);
+					None
+				},
+			};
+			continue
+		}
+
+		let changes: Vec<_> = notification
+			.changes
+			.iter()
+			.filter(|(child, _, _)| child.is_none())
+			.map(|(_, key, value)| (key, value))
+			.collect();
+		let timestamp = changes
+			.iter()
+			.find(|(key, _)| key.0 == timestamp_key)
+			.and_then(|(_, value)| u64::decode(&mut &value?.0[..]).ok());
+
+		let at = At { number, timestamp };
+		last_indexed = match index.apply_changes(changes.into_iter(), at) {
+			Ok(()) => Some(hash),
+			Err(e) => {
+				log::error!("Indexer failed to index block #{}: {}", number, e);
+				None
+			},
+		};
+	}
+}
+
+/// Make `index` match the state of `client` at the block `hash`.
+fn reconcile<C>(
+	client: &C,
+	index: &Index,
+	hash: <Block as BlockT>::Hash,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+	C: StorageProvider<Block, sc_service::TFullBackend<Block>> + HeaderBackend<Block>,
+{
+	let number = client.number(hash)?.ok_or("Unknown block")?;
+	let timestamp = client
+		.storage(hash, &StorageKey(storage_prefix("Timestamp", "Now")))?
+		.and_then(|value| u64::decode(&mut &value.0[..]).ok());
+
+	let mut records = Vec::new();
+	for kind in Kind::ALL {
+		let prefix = kind.storage_prefix();
+		let pairs = client
+			.storage_pairs(hash, &StorageKey(prefix.clone()))?
+			.into_iter()
+			.filter_map(|(key, value)| {
+				let id = key.0.get(prefix.len() + TWOX64_LENGTH..)?;
+				Some((u32::decode(&mut &id[..]).ok()?, value))
+			})
+			.collect();
+		records.push((kind, pairs));
+	}
+
+	index.reconcile(&records, At { number, timestamp })?;
+	Ok(())
+}
+
+/// RPC methods for querying the index.
+#[rpc(client, server)]
+pub trait IndexApi {
+	/// Find the records matching `query`.
+	#[method(name = "vf_queryRecords")]
+	fn query_records(&self, query: RecordQuery) -> RpcResult<Vec<IndexedRecord>>;
+}
+
+/// Implementation of [`IndexApiServer`] on top of an [`Index`].
+pub struct IndexRpc {
+	index: Arc<Index>,
+}
+
+impl IndexRpc {
+	/// Create a new RPC handler serving `index`.
+	pub fn new(index: Arc<Index>) -> Self {
+		Self { index }
+	}
+}
+
+#[async_trait]
+impl IndexApiServer for IndexRpc {
+	fn query_records(&self, query: RecordQuery) -> RpcResult<Vec<IndexedRecord>> {
+		self.index.query(&query).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(1, "Index query failed", Some(e.to_string())))
+				.into()
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use node_template_runtime::pallet_valueflows_agent::ConceptId;
+	use sp_core::hashing::twox_64;
+
+	fn alice() -> AccountId {
+		AccountId::new([1; 32])
+	}
+
+	fn bob() -> AccountId {
+		AccountId::new([2; 32])
+	}
+
+	fn index() -> Index {
+		Index::new(Connection::open_in_memory().unwrap()).unwrap()
+	}
+
+	fn at(number: u32) -> At {
+		At { number, timestamp: Some(number as u64 * 6_000) }
+	}
+
+	fn key(kind: Kind, id: u32) -> StorageKey {
+		StorageKey([kind.storage_prefix(), twox_64(&id.encode()).to_vec(), id.encode()].concat())
+	}
+
+	fn unit(owner: AccountId, label: &[u8]) -> StorageData {
+		let unit = Unit::<Runtime> {
+			owner,
+			deposit: 0,
+			label: label.to_vec().try_into().unwrap(),
+			symbol: Default::default(),
+		};
+		StorageData(unit.encode())
+	}
+
+	fn concept(label: &[u8], broader: Option<u32>) -> StorageData {
+		let concept = Concept::<Runtime> {
+			owner: alice(),
+			deposit: 0,
+			scheme: Default::default(),
+			label: label.to_vec().try_into().unwrap(),
+			broader: broader.map(ConceptId),
+		};
+		StorageData(concept.encode())
+	}
+
+	fn resource_spec(owner: AccountId, concept_id: u32, archived_at: Option<u32>) -> StorageData {
+		let resource_spec = ResourceSpecification::<Runtime> {
+			owner,
+			deposit: 0,
+			name: b"apple".to_vec().try_into().unwrap(),
+			images: Default::default(),
+			note: None,
+			resource_classified_as: vec![ConceptId(concept_id)].try_into().unwrap(),
+			default_unit_of_resource_id: None,
+			default_unit_of_effort_id: None,
+			revision: 0,
+			previous_version: None,
+			archived_at,
+		};
+		StorageData(resource_spec.encode())
+	}
+
+	fn apply(index: &Index, changes: &[(StorageKey, Option<StorageData>)], at: At) {
+		let changes = changes.iter().map(|(key, value)| (key, value.as_ref()));
+		index.apply_changes(changes, at).unwrap();
+	}
+
+	fn ids(records: Vec<IndexedRecord>) -> Vec<u32> {
+		records.into_iter().map(|record| record.id).collect()
+	}
+
+	#[test]
+	fn apply_changes_inserts_updates_and_removes_records() {
+		let index = index();
+		let other = StorageKey(storage_prefix("System", "Number"));
+		apply(&index, &[(key(Kind::Unit, 0), Some(unit(alice(), b"kg"))), (other, None)], at(1));
+		apply(&index, &[(key(Kind::Unit, 0), Some(unit(bob(), b"kilogram")))], at(2));
+
+		let records = index.query(&RecordQuery::default()).unwrap();
+		assert_eq!(records.len(), 1);
+		assert_eq!(records[0].kind, Kind::Unit);
+		assert_eq!(records[0].owner, bob().to_ss58check());
+		assert_eq!(records[0].name, "kilogram");
+		assert_eq!((records[0].created_block, records[0].created_at), (1, Some(6_000)));
+		assert_eq!((records[0].updated_block, records[0].updated_at), (2, Some(12_000)));
+
+		apply(&index, &[(key(Kind::Unit, 0), None)], at(3));
+		assert!(index.query(&RecordQuery::default()).unwrap().is_empty());
+	}
+
+	#[test]
+	fn apply_changes_rejects_undecodable_records() {
+		let index = index();
+		let garbage = StorageData(vec![1, 2, 3]);
+		let changes =
+			[(key(Kind::Unit, 0), Some(unit(alice(), b"kg"))), (key(Kind::Unit, 1), Some(garbage))];
+		let changes = changes.iter().map(|(key, value)| (key, value.as_ref()));
+
+		assert!(matches!(index.apply_changes(changes, at(1)), Err(Error::Decode(Kind::Unit, 1))));
+		assert!(index.query(&RecordQuery::default()).unwrap().is_empty());
+	}
+
+	#[test]
+	fn reconcile_removes_deleted_records() {
+		let index = index();
+		apply(
+			&index,
+			&[
+				(key(Kind::Unit, 0), Some(unit(alice(), b"kg"))),
+				(key(Kind::Unit, 1), Some(unit(alice(), b"g"))),
+			],
+			at(1),
+		);
+
+		let units = vec![(1, unit(bob(), b"gram")), (2, unit(bob(), b"m"))];
+		index.reconcile(&[(Kind::Unit, units)], at(5)).unwrap();
+
+		let records = index.query(&RecordQuery::default()).unwrap();
+		assert_eq!(ids(records.clone()), vec![1, 2]);
+		assert_eq!(records[0].name, "gram");
+		assert_eq!(records[0].owner, bob().to_ss58check());
+		assert_eq!(records[0].created_block, 1);
+		assert_eq!(records[1].created_block, 5);
+	}
+
+	#[test]
+	fn classified_as_includes_narrower_concepts() {
+		let index = index();
+		apply(
+			&index,
+			&[
+				(key(Kind::Concept, 0), Some(concept(b"food", None))),
+				(key(Kind::Concept, 1), Some(concept(b"fruit", Some(0)))),
+				(key(Kind::Concept, 2), Some(concept(b"apples", Some(1)))),
+				(key(Kind::Concept, 3), Some(concept(b"tools", None))),
+				(key(Kind::ResourceSpecification, 0), Some(resource_spec(alice(), 2, None))),
+				(key(Kind::ResourceSpecification, 1), Some(resource_spec(bob(), 1, None))),
+				(key(Kind::ResourceSpecification, 2), Some(resource_spec(alice(), 3, None))),
+				(key(Kind::ResourceSpecification, 3), Some(resource_spec(alice(), 0, Some(2)))),
+			],
+			at(1),
+		);
+
+		let query = RecordQuery { classified_as: Some(0), ..Default::default() };
+		let records = index.query(&query).unwrap();
+		assert_eq!(ids(records.clone()), vec![0, 1]);
+		assert_eq!(records[0].classified_as, vec![2]);
+		let query = RecordQuery { classified_as: Some(1), ..Default::default() };
+		assert_eq!(ids(index.query(&query).unwrap()), vec![0, 1]);
+		let query = RecordQuery { classified_as: Some(2), ..Default::default() };
+		assert_eq!(ids(index.query(&query).unwrap()), vec![0]);
+
+		// Placeholders are numbered across filters.
+		let query = RecordQuery {
+			owner: Some(alice()),
+			classified_as: Some(0),
+			include_archived: true,
+			..Default::default()
+		};
+		let records = index.query(&query).unwrap();
+		assert_eq!(ids(records.clone()), vec![0, 3]);
+		assert_eq!(records[1].archived_block, Some(2));
+	}
+
+	#[test]
+	fn query_fails_on_unknown_kinds() {
+		let index = index();
+		apply(&index, &[(key(Kind::Unit, 0), Some(unit(alice(), b"kg")))], at(1));
+		index
+			.conn
+			.lock()
+			.unwrap()
+			.execute("UPDATE records SET kind = 'widget' WHERE kind = 'unit'", [])
+			.unwrap();
+
+		assert!(index.query(&RecordQuery::default()).is_err());
+	}
+
+	#[test]
+	fn query_sorts_and_pages_records() {
+		let index = index();
+		let changes: Vec<_> = (0..MAX_LIMIT + 1)
+			.map(|id| (key(Kind::Unit, id), Some(unit(alice(), format!("{:04}", id).as_bytes()))))
+			.collect();
+		apply(&index, &changes, at(1));
+
+		let query = RecordQuery {
+			order_by: OrderBy::Name,
+			descending: true,
+			limit: Some(2),
+			offset: Some(1),
+			..Default::default()
+		};
+		assert_eq!(ids(index.query(&query).unwrap()), vec![MAX_LIMIT - 1, MAX_LIMIT - 2]);
+
+		assert_eq!(index.query(&RecordQuery::default()).unwrap().len(), DEFAULT_LIMIT as usize);
+		let query = RecordQuery { limit: Some(u32::MAX), ..Default::default() };
+		assert_eq!(index.query(&query).unwrap().len(), MAX_LIMIT as usize);
+	}
+}
@@ -0,0 +1,919 @@
+//! Exchange of the agent pallet records with other ValueFlows implementations as JSON-LD.
+//!
+//! `export-json-ld` writes the agents, units, spatial things, specifications and concepts at a
+//! block as a JSON-LD graph using the ValueFlows vocabulary, along with the units of measure,
+//! geo and SKOS vocabularies it builds on. Records are identified by their global id, agents by
+//! their SS58 address. Specifications also carry their revision and, once archived, the block they
+//! were archived in, terms of this chain that ValueFlows lacks, under the `vfs` prefix.
+//!
+//! `import-json-ld` adds such a graph to the genesis config of the agent pallet in a chain spec,
+//! raising the endowments of the creators of the records by their deposits. The graph is expanded
+//! first, so graphs produced by other implementations can be imported whatever context they use,
+//! as long as it is inline. References between nodes are resolved by `@id`, and agents may be
+//! identified by any IRI ending in their SS58 address. The graph is checked against the bounds of
+//! the runtime, so the genesis config it produces can be built. Archived specifications are
+//! archived again at genesis, starting a new revision history. Images are not part of the genesis
+//! config, as their URIs lack the size and media type the pallet stores, so resource
+//! specifications with images are rejected.
+
+use std::{
+	collections::{hash_map::Entry, BTreeSet, HashMap},
+	fs,
+	path::PathBuf,
+	sync::Arc,
+};
+
+use futures::executor::block_on;
+use json_ld::{syntax::IntoJson, IriBuf, JsonLdProcessor, NoLoader, RemoteDocument};
+use node_template_runtime::{
+	opaque::Block,
+	pallet_valueflows_agent::{
+		self as agent, ConceptId, ConceptSchemeId, ProcessSpecificationId, RecordId,
+		ResourceSpecificationId, UnitId,
+	},
+	AccountId, Balance, BalancesConfig, Runtime, ValueflowsAgentConfig, EXISTENTIAL_DEPOSIT,
+};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use serde_json::{json, Value};
+use sp_blockchain::HeaderBackend;
+use sp_core::{crypto::Ss58Codec, H256};
+use sp_runtime::{traits::Get, FixedI64};
+
+use crate::{
+	service::FullClient,
+	storage::{AgentStorage, Error, Hasher},
+};
+
+/// Prefix of the `@id` of exported agents, followed by their SS58 address.
+const AGENT_ID_PREFIX: &str = "urn:ss58:";
+
+/// Prefixes of the compacted terms of an export, and the vocabularies they stand for.
+const PREFIXES: &[(&str, &str)] = &[
+	("vf", "https://w3id.org/valueflows/ont/vf#"),
+	("vfs", "urn:valueflows:terms:"),
+	("om2", "http://www.ontology-of-units-of-measure.org/resource/om-2/"),
+	("geo", "http://www.w3.org/2003/01/geo/wgs84_pos#"),
+	("skos", "http://www.w3.org/2004/02/skos/core#"),
+	("foaf", "http://xmlns.com/foaf/0.1/"),
+	("dcterms", "http://purl.org/dc/terms/"),
+	("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+];
+
+/// Largest text accepted as a note, the largest preimage `pallet_preimage` stores.
+const MAX_TEXT_LENGTH: usize = 4 * 1024 * 1024;
+
+/// Bytes of a stored record besides its strings and lists, more than any record takes. Used to
+/// estimate the deposits of imported records.
+const RECORD_OVERHEAD: usize = 256;
+
+type MaxNameLength = <Runtime as agent::Config>::MaxNameLength;
+type MaxSymbolLength = <Runtime as agent::Config>::MaxSymbolLength;
+type MaxArrayLength = <Runtime as agent::Config>::MaxArrayLength;
+
+/// Export the ValueFlows records at a block as JSON-LD.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportJsonLdCmd {
+	/// Block hash or number to export, the best block by default.
+	#[arg(value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// File to write the graph to, stdout by default.
+	#[arg(long, short)]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportJsonLdCmd {
+	/// Run the command against `client`.
+	pub async fn run(&self, client: Arc<FullClient>) -> sc_cli::Result<()> {
+		let info = client.info();
+		let at = match &self.at {
+			Some(at) => client.expect_block_hash_from_id(&at.parse::<Block>()?)?,
+			None => info.best_hash,
+		};
+		let graph = export(&AgentStorage::new(&client, at), info.genesis_hash)?;
+		write(self.output.as_ref(), &graph)
+	}
+}
+
+impl CliConfiguration for ExportJsonLdCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// Add a JSON-LD graph of ValueFlows records to the genesis config of the agent pallet in a chain
+/// spec.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ImportJsonLdCmd {
+	/// JSON-LD file to import.
+	pub input: PathBuf,
+
+	/// Chain spec to add the records to, not in its raw form, e.g. one written by `build-spec`.
+	#[arg(long)]
+	pub chain_spec: PathBuf,
+
+	/// File to write the chain spec to, stdout by default.
+	#[arg(long, short)]
+	pub output: Option<PathBuf>,
+}
+
+impl ImportJsonLdCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let graph: Value = serde_json::from_slice(&fs::read(&self.input)?)
+			.map_err(|e| format!("Invalid JSON-LD in {:?}: {}", self.input, e))?;
+		let mut chain_spec: Value = serde_json::from_slice(&fs::read(&self.chain_spec)?)
+			.map_err(|e| format!("Invalid chain spec in {:?}: {}", self.chain_spec, e))?;
+		merge(&mut chain_spec, import(&graph)?)?;
+		write(self.output.as_ref(), &chain_spec)
+	}
+}
+
+/// Genesis entries produced by an import.
+struct ImportedGenesis {
+	/// Balances of the record creators, covering the deposits of their records.
+	balances: BalancesConfig,
+	valueflows_agent: ValueflowsAgentConfig,
+}
+
+fn write(output: Option<&PathBuf>, value: &impl serde::Serialize) -> sc_cli::Result<()> {
+	let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+	match output {
+		Some(path) => fs::write(path, json)?,
+		None => println!("{}", json),
+	}
+	Ok(())
+}
+
+fn context() -> Value {
+	PREFIXES.iter().map(|(prefix, iri)| (prefix.to_string(), json!(iri))).collect()
+}
+
+/// The IRI the compacted `term` stands for.
+fn iri(term: &str) -> String {
+	let (prefix, name) = term.split_once(':').expect("terms are compacted; qed");
+	let (_, vocabulary) = PREFIXES
+		.iter()
+		.find(|(known, _)| *known == prefix)
+		.expect("terms use the prefixes of the context; qed");
+	format!("{}{}", vocabulary, name)
+}
+
+/// Expand `document`, so that its nodes hold the IRIs of its terms, whatever its context.
+/// Remote contexts are not loaded.
+fn expand(document: &Value) -> Result<Vec<Value>, String> {
+	let document = RemoteDocument::<IriBuf>::new(
+		None,
+		None,
+		json_syntax::Value::from_serde_json(document.clone()),
+	);
+	let expanded =
+		block_on(document.expand(&mut NoLoader)).map_err(|e| format!("Invalid JSON-LD: {}", e))?;
+	let nodes = match expanded.into_json().into_serde_json() {
+		Value::Array(nodes) => nodes,
+		node => vec![node],
+	};
+	// Nodes of a named graph are imported along with those of the default graph.
+	Ok(nodes
+		.into_iter()
+		.flat_map(|node| match &node["@graph"] {
+			Value::Array(graph) => graph.clone(),
+			_ => vec![node],
+		})
+		.collect())
+}
+
+fn agent_id(account: &AccountId) -> String {
+	format!("{}{}", AGENT_ID_PREFIX, account.to_ss58check())
+}
+
+/// The `@id` of a record, its global id as derived by the pallet.
+fn record_id<I: RecordId>(genesis_hash: H256, id: I) -> String {
+	let global_id = id.global_id::<<Runtime as frame_system::Config>::Hashing>(genesis_hash);
+	format!("urn:valueflows:{:?}", global_id)
+}
+
+/// A reference to the node identified by `id`.
+fn reference(id: String) -> Value {
+	json!({ "@id": id })
+}
+
+fn string(bytes: &[u8]) -> String {
+	String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// The URI of an image, as a base16 CIDv1. CIDv0 are converted to the equivalent CIDv1.
+fn image_uri(cid: &[u8]) -> String {
+	let cid = match cid {
+		[0x12, 0x20, ..] => [&[0x01, 0x70][..], cid].concat(),
+		_ => cid.to_vec(),
+	};
+	format!("ipfs://f{}", cid.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+/// Sorted entries of a record map, so that records are listed in the order they were created.
+fn records<I: codec::Decode + Ord, V: codec::Decode>(
+	storage: &AgentStorage,
+	item: &str,
+) -> Result<Vec<(I, V)>, Error> {
+	let mut records = storage.entries(item, Hasher::Twox64Concat)?;
+	records.sort_by(|(a, _), (b, _)| a.cmp(b));
+	Ok(records)
+}
+
+fn export(storage: &AgentStorage, genesis_hash: H256) -> Result<Value, Error> {
+	let mut graph = Vec::new();
+	let creator = |owner: &AccountId| reference(agent_id(owner));
+
+	for (account, _) in storage.entries::<AccountId, bool>("Agents", Hasher::Blake2_128Concat)? {
+		graph.push(json!({ "@type": "foaf:Agent", "@id": agent_id(&account) }));
+	}
+	for (id, unit) in records::<UnitId, agent::Unit<Runtime>>(storage, "Units")? {
+		graph.push(json!({
+			"@type": "om2:Unit",
+			"@id": record_id(genesis_hash, id),
+			"rdfs:label": string(&unit.label),
+			"om2:symbol": string(&unit.symbol),
+			"dcterms:creator": creator(&unit.owner),
+		}));
+	}
+	for (id, spatial_thing) in
+		records::<agent::SpatialThingId, agent::SpatialThing<Runtime>>(storage, "SpatialThings")?
+	{
+		graph.push(json!({
+			"@type": "geo:SpatialThing",
+			"@id": record_id(genesis_hash, id),
+			"vf:name": string(&spatial_thing.name),
+			"vf:note": storage.text(spatial_thing.note)?,
+			"vf:mappableAddress": storage.text(spatial_thing.mappable_address)?,
+			"geo:lat": spatial_thing.lat.map(FixedI64::to_float),
+			"geo:long": spatial_thing.long.map(FixedI64::to_float),
+			"geo:alt": spatial_thing.alt.map(FixedI64::to_float),
+			"dcterms:creator": creator(&spatial_thing.owner),
+		}));
+	}
+	for (id, process_spec) in records::<
+		agent::ProcessSpecificationId,
+		agent::ProcessSpecification<Runtime>,
+	>(storage, "ProcessSpecifications")?
+	{
+		graph.push(json!({
+			"@type": "vf:ProcessSpecification",
+			"@id": record_id(genesis_hash, id),
+			"vf:name": string(&process_spec.name),
+			"vf:note": storage.text(process_spec.note)?,
+			"vfs:revision": process_spec.revision,
+			"vfs:archivedAt": process_spec.archived_at,
+			"dcterms:creator": creator(&process_spec.owner),
+		}));
+	}
+	for (id, scheme) in
+		records::<ConceptSchemeId, agent::ConceptScheme<Runtime>>(storage, "ConceptSchemes")?
+	{
+		graph.push(json!({
+			"@type": "skos:ConceptScheme",
+			"@id": record_id(genesis_hash, id),
+			"skos:prefLabel": string(&scheme.name),
+			"dcterms:creator": creator(&scheme.owner),
+		}));
+	}
+	let concept = |id: ConceptId| reference(record_id(genesis_hash, id));
+	let unit = |id: UnitId| reference(record_id(genesis_hash, id));
+	for (id, concept_record) in records::<ConceptId, agent::Concept<Runtime>>(storage, "Concepts")?
+	{
+		graph.push(json!({
+			"@type": "skos:Concept",
+			"@id": record_id(genesis_hash, id),
+			"skos:prefLabel": string(&concept_record.label),
+			"skos:inScheme": reference(record_id(genesis_hash, concept_record.scheme)),
+			"skos:broader": concept_record.broader.map(concept),
+		}));
+	}
+	for (id, resource_spec) in records::<
+		agent::ResourceSpecificationId,
+		agent::ResourceSpecification<Runtime>,
+	>(storage, "ResourceSpecifications")?
+	{
+		let images: Vec<_> =
+			resource_spec.images.iter().map(|image| image_uri(&image.cid)).collect();
+		let classified_as: Vec<_> =
+			resource_spec.resource_classified_as.iter().copied().map(concept).collect();
+		graph.push(json!({
+			"@type": "vf:ResourceSpecification",
+			"@id": record_id(genesis_hash, id),
+			"vf:name": string(&resource_spec.name),
+			"vf:note": storage.text(resource_spec.note)?,
+			"vf:image": images,
+			"vf:resourceClassifiedAs": classified_as,
+			"vf:defaultUnitOfResource": resource_spec.default_unit_of_resource_id.map(unit),
+			"vf:defaultUnitOfEffort": resource_spec.default_unit_of_effort_id.map(unit),
+			"vfs:revision": resource_spec.revision,
+			"vfs:archivedAt": resource_spec.archived_at,
+			"dcterms:creator": creator(&resource_spec.owner),
+		}));
+	}
+
+	Ok(json!({ "@context": context(), "@graph": graph }))
+}
+
+/// A node of the expanded graph. Terms are given compacted and looked up by the IRI they stand
+/// for, their values being lists of value objects or node references.
+struct Node<'a>(&'a Value);
+
+static NULL: Value = Value::Null;
+
+impl<'a> Node<'a> {
+	fn id(&self) -> Result<&'a str, String> {
+		self.0["@id"].as_str().ok_or_else(|| format!("Node without @id: {}", self.0))
+	}
+
+	/// Whether the node is of the compacted type `ty`.
+	fn is(&self, ty: &str) -> bool {
+		let ty = iri(ty);
+		self.0["@type"].as_array().map_or(false, |types| types.iter().any(|t| *t == ty))
+	}
+
+	/// The values of `term`.
+	fn values(&self, term: &str) -> &'a [Value] {
+		self.0[iri(term)].as_array().map_or(&[], Vec::as_slice)
+	}
+
+	/// The first value of `term`.
+	fn value(&self, term: &str) -> &'a Value {
+		self.values(term).first().map_or(&NULL, |value| &value["@value"])
+	}
+
+	/// The note or other long text of `term`, if any.
+	fn text(&self, term: &str) -> Result<Option<Vec<u8>>, String> {
+		let text = self.value(term).as_str().map(|text| text.as_bytes().to_vec());
+		self.ensure_at_most(term, len(&text), MAX_TEXT_LENGTH)?;
+		Ok(text)
+	}
+
+	/// The text of `term`, which must fit the bound `S` of the pallet.
+	fn required_text<S: Get<u32>>(&self, term: &str) -> Result<Vec<u8>, String> {
+		let text = self.value(term).as_str().map(|text| text.as_bytes().to_vec());
+		let text = text.ok_or_else(|| format!("{} has no {}", self.0["@id"], term))?;
+		self.ensure_at_most(term, text.len(), S::get() as usize)?;
+		Ok(text)
+	}
+
+	fn ensure_at_most(&self, term: &str, len: usize, max: usize) -> Result<(), String> {
+		if len > max {
+			return Err(format!("{} of {} is longer than {}", term, self.0["@id"], max))
+		}
+		Ok(())
+	}
+
+	fn number(&self, term: &str) -> Option<FixedI64> {
+		self.value(term).as_f64().map(FixedI64::from_float)
+	}
+
+	/// Whether `term` holds a value.
+	fn has(&self, term: &str) -> bool {
+		!self.values(term).is_empty()
+	}
+
+	/// The `@id` referenced by `term`.
+	fn reference(&self, term: &str) -> Option<&'a str> {
+		self.values(term).first()?["@id"].as_str()
+	}
+
+	/// The `@id`s referenced by `term`.
+	fn references(&self, term: &str) -> Vec<&'a str> {
+		self.values(term)
+			.iter()
+			.filter_map(|reference| reference["@id"].as_str())
+			.collect()
+	}
+
+	fn creator(&self) -> Result<AccountId, String> {
+		let creator = self
+			.reference("dcterms:creator")
+			.ok_or_else(|| format!("{} has no creator", self.0["@id"]))?;
+		account(creator)
+	}
+}
+
+/// Length of an optional text.
+fn len(text: &Option<Vec<u8>>) -> usize {
+	text.as_ref().map_or(0, Vec::len)
+}
+
+/// The agents of an import, and the records and deposits of those creating records.
+#[derive(Default)]
+struct Agents {
+	accounts: Vec<AccountId>,
+	deposits: HashMap<AccountId, (u32, Balance)>,
+}
+
+impl Agents {
+	fn register(&mut self, account: AccountId) -> AccountId {
+		if !self.accounts.contains(&account) {
+			self.accounts.push(account.clone());
+		}
+		account
+	}
+
+	/// Register `account` as the creator of a record holding `bytes` of strings and lists.
+	fn create(&mut self, account: AccountId, bytes: usize) -> Result<AccountId, String> {
+		let max_records = <Runtime as agent::Config>::MaxOwnedRecords::get();
+		let len = (RECORD_OVERHEAD + bytes) as Balance;
+		let deposit = <Runtime as agent::Config>::DepositBase::get() +
+			<Runtime as agent::Config>::DepositPerByte::get() * len;
+		let (records, deposits) = match self.deposits.entry(account.clone()) {
+			Entry::Occupied(entry) => entry.into_mut(),
+			Entry::Vacant(entry) => entry.insert((0, EXISTENTIAL_DEPOSIT)),
+		};
+		if *records == max_records {
+			return Err(format!("{} creates more than {} records", agent_id(&account), max_records))
+		}
+		*records += 1;
+		*deposits += deposit;
+		Ok(self.register(account))
+	}
+
+	/// The balances covering the deposits of the creators.
+	fn balances(&self) -> BalancesConfig {
+		let balances = self
+			.accounts
+			.iter()
+			.filter_map(|account| Some((account.clone(), self.deposits.get(account)?.1)))
+			.collect();
+		BalancesConfig { balances }
+	}
+}
+
+/// The account of the agent identified by `id`, an IRI ending in its SS58 address, e.g.
+/// `urn:ss58:<address>` or `https://example.com/agents/<address>`.
+fn account(id: &str) -> Result<AccountId, String> {
+	let address = id.rsplit(|c| matches!(c, ':' | '/' | '#')).next().unwrap_or(id);
+	AccountId::from_ss58check(address).map_err(|_| format!("Invalid agent {}", id))
+}
+
+/// Local ids of the records of one type, by `@id`, in the order they are created at genesis.
+fn local_ids<'a>(nodes: &[Node<'a>]) -> Result<HashMap<&'a str, u32>, String> {
+	nodes
+		.iter()
+		.enumerate()
+		.map(|(index, node)| Ok((node.id()?, index as u32)))
+		.collect()
+}
+
+fn nodes_of<'a>(graph: &'a [Value], ty: &str) -> Vec<Node<'a>> {
+	graph.iter().map(Node).filter(|node| node.is(ty)).collect()
+}
+
+fn lookup(ids: &HashMap<&str, u32>, id: &str) -> Result<u32, String> {
+	ids.get(id).copied().ok_or_else(|| format!("Unknown reference {}", id))
+}
+
+fn import(document: &Value) -> Result<ImportedGenesis, String> {
+	let graph = expand(document)?;
+	let nodes = |ty: &str| nodes_of(&graph, ty);
+
+	let units = nodes("om2:Unit");
+	let spatial_things = nodes("geo:SpatialThing");
+	let process_specs = nodes("vf:ProcessSpecification");
+	let resource_specs = nodes("vf:ResourceSpecification");
+	let schemes = nodes("skos:ConceptScheme");
+	// Broader concepts must be created before their narrower concepts.
+	let mut pending = nodes("skos:Concept");
+	let mut concepts = Vec::new();
+	while !pending.is_empty() {
+		let created: BTreeSet<_> = concepts.iter().map(Node::id).collect::<Result<_, _>>()?;
+		let (ready, rest): (Vec<_>, Vec<_>) = pending.into_iter().partition(|concept| {
+			concept
+				.reference("skos:broader")
+				.map_or(true, |broader| created.contains(broader))
+		});
+		if ready.is_empty() {
+			let concept = &rest[0].0["@id"];
+			return Err(format!("Concept {} has an unknown or cyclic broader concept", concept))
+		}
+		concepts.extend(ready);
+		pending = rest;
+	}
+
+	let unit_ids = local_ids(&units)?;
+	let scheme_ids = local_ids(&schemes)?;
+	let concept_ids = local_ids(&concepts)?;
+
+	let mut agents = Agents::default();
+	for agent in nodes("foaf:Agent") {
+		agents.register(account(agent.id()?)?);
+	}
+
+	let mut genesis = ValueflowsAgentConfig::default();
+	for unit in units {
+		let label = unit.required_text::<MaxNameLength>("rdfs:label")?;
+		let symbol = unit.required_text::<MaxSymbolLength>("om2:symbol")?;
+		let creator = agents.create(unit.creator()?, label.len() + symbol.len())?;
+		genesis.units.push((creator, label, symbol));
+	}
+	for spatial_thing in spatial_things {
+		let name = spatial_thing.required_text::<MaxNameLength>("vf:name")?;
+		let note = spatial_thing.text("vf:note")?;
+		let mappable_address = spatial_thing.text("vf:mappableAddress")?;
+		let bytes = name.len() + len(&note) + len(&mappable_address);
+		genesis.spatial_things.push((
+			agents.create(spatial_thing.creator()?, bytes)?,
+			name,
+			note,
+			mappable_address,
+			spatial_thing.number("geo:lat"),
+			spatial_thing.number("geo:long"),
+			spatial_thing.number("geo:alt"),
+		));
+	}
+	for (id, process_spec) in process_specs.into_iter().enumerate() {
+		let name = process_spec.required_text::<MaxNameLength>("vf:name")?;
+		let note = process_spec.text("vf:note")?;
+		let creator = agents.create(process_spec.creator()?, name.len() + len(&note))?;
+		genesis.process_specifications.push((creator, name, note));
+		if process_spec.has("vfs:archivedAt") {
+			genesis.archived_process_specifications.push(ProcessSpecificationId(id as u32));
+		}
+	}
+	for scheme in schemes {
+		let name = scheme.required_text::<MaxNameLength>("skos:prefLabel")?;
+		let creator = agents.create(scheme.creator()?, name.len())?;
+		genesis.concept_schemes.push((creator, name));
+	}
+	for concept in &concepts {
+		let scheme = concept
+			.reference("skos:inScheme")
+			.ok_or_else(|| format!("{} is in no scheme", concept.0["@id"]))?;
+		let scheme = lookup(&scheme_ids, scheme)?;
+		let broader = concept
+			.reference("skos:broader")
+			.map(|broader| lookup(&concept_ids, broader))
+			.transpose()?;
+		// Broader concepts come first, so they are already in the genesis config.
+		if let Some(broader) = broader {
+			let ConceptSchemeId(broader_scheme) = genesis.concepts[broader as usize].0;
+			if broader_scheme != scheme {
+				return Err(format!("{} is not in the scheme of its broader concept", concept.id()?))
+			}
+		}
+		let label = concept.required_text::<MaxNameLength>("skos:prefLabel")?;
+		// Concepts are owned by the owner of their scheme.
+		agents.create(genesis.concept_schemes[scheme as usize].0.clone(), label.len())?;
+		genesis.concepts.push((ConceptSchemeId(scheme), label, broader.map(ConceptId)));
+	}
+	for (id, resource_spec) in resource_specs.into_iter().enumerate() {
+		if resource_spec.has("vf:image") {
+			return Err(format!("{} has images, which cannot be imported", resource_spec.id()?))
+		}
+		let unit = |term| {
+			resource_spec
+				.reference(term)
+				.map(|unit| lookup(&unit_ids, unit).map(UnitId))
+				.transpose()
+		};
+		let name = resource_spec.required_text::<MaxNameLength>("vf:name")?;
+		let note = resource_spec.text("vf:note")?;
+		let classified_as = resource_spec
+			.references("vf:resourceClassifiedAs")
+			.into_iter()
+			.map(|concept| lookup(&concept_ids, concept).map(ConceptId))
+			.collect::<Result<Vec<_>, _>>()?;
+		let max_classifications = MaxArrayLength::get() as usize;
+		resource_spec.ensure_at_most(
+			"vf:resourceClassifiedAs",
+			classified_as.len(),
+			max_classifications,
+		)?;
+		let bytes = name.len() + len(&note) + classified_as.len() * 4;
+		genesis.resource_specifications.push((
+			agents.create(resource_spec.creator()?, bytes)?,
+			name,
+			note,
+			classified_as,
+			unit("vf:defaultUnitOfResource")?,
+			unit("vf:defaultUnitOfEffort")?,
+		));
+		if resource_spec.has("vfs:archivedAt") {
+			genesis
+				.archived_resource_specifications
+				.push(ResourceSpecificationId(id as u32));
+		}
+	}
+	genesis.agents = agents.accounts.clone();
+
+	Ok(ImportedGenesis { balances: agents.balances(), valueflows_agent: genesis })
+}
+
+/// Add `imported` to the genesis config of `chain_spec`. The deposits are added to the endowments
+/// of creators already endowed, as an account endowed twice fails the genesis build. The agent
+/// pallet config of the chain spec must have no records, as their ids would clash.
+fn merge(chain_spec: &mut Value, imported: ImportedGenesis) -> Result<(), String> {
+	let runtime = chain_spec
+		.pointer_mut("/genesis/runtime")
+		.ok_or("The chain spec has no genesis.runtime, it may be in its raw form")?;
+
+	let balances = &mut runtime["balances"]["balances"];
+	let mut endowments: Vec<(AccountId, Balance)> = serde_json::from_value(balances.take())
+		.map_err(|e| format!("Invalid balances in the chain spec: {}", e))?;
+	for (account, deposits) in imported.balances.balances {
+		match endowments.iter_mut().find(|(endowed, _)| *endowed == account) {
+			Some((_, endowment)) => *endowment = endowment.saturating_add(deposits),
+			None => endowments.push((account, deposits)),
+		}
+	}
+	*balances = serde_json::to_value(endowments).map_err(|e| e.to_string())?;
+
+	let config = &mut runtime["valueflowsAgent"];
+	let mut genesis = match config.take() {
+		Value::Null => ValueflowsAgentConfig::default(),
+		existing => serde_json::from_value(existing)
+			.map_err(|e| format!("Invalid agent pallet config in the chain spec: {}", e))?,
+	};
+	let has_records = !genesis.units.is_empty() ||
+		!genesis.spatial_things.is_empty() ||
+		!genesis.process_specifications.is_empty() ||
+		!genesis.resource_specifications.is_empty() ||
+		!genesis.concept_schemes.is_empty();
+	if has_records {
+		return Err("The chain spec already has agent pallet records".into())
+	}
+	let imported = imported.valueflows_agent;
+	for agent in imported.agents {
+		if !genesis.agents.contains(&agent) {
+			genesis.agents.push(agent);
+		}
+	}
+	*config = serde_json::to_value(ValueflowsAgentConfig {
+		agents: genesis.agents,
+		registration_mode: genesis.registration_mode,
+		..imported
+	})
+	.map_err(|e| e.to_string())?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn alice() -> AccountId {
+		AccountId::new([1; 32])
+	}
+
+	fn creator() -> Value {
+		reference(agent_id(&alice()))
+	}
+
+	fn scheme(id: &str) -> Value {
+		json!({
+			"@type": "skos:ConceptScheme",
+			"@id": id,
+			"skos:prefLabel": "products",
+			"dcterms:creator": creator(),
+		})
+	}
+
+	fn concept(id: &str, scheme: &str, broader: Option<&str>) -> Value {
+		json!({
+			"@type": "skos:Concept",
+			"@id": id,
+			"skos:prefLabel": id,
+			"skos:inScheme": reference(scheme.to_owned()),
+			"skos:broader": broader.map(|broader| reference(broader.to_owned())),
+		})
+	}
+
+	fn resource_spec(name: &str, classified_as: &[&str]) -> Value {
+		let classified_as: Vec<_> =
+			classified_as.iter().map(|concept| reference(concept.to_string())).collect();
+		json!({
+			"@type": "vf:ResourceSpecification",
+			"@id": "urn:test:apple",
+			"vf:name": name,
+			"vf:note": "ripe",
+			"vf:resourceClassifiedAs": classified_as,
+			"vf:defaultUnitOfResource": reference("urn:test:kg".to_owned()),
+			"dcterms:creator": creator(),
+		})
+	}
+
+	fn import_graph(nodes: Vec<Value>) -> Result<ImportedGenesis, String> {
+		let unit = json!({
+			"@type": "om2:Unit",
+			"@id": "urn:test:kg",
+			"rdfs:label": "kilogram",
+			"om2:symbol": "kg",
+			"dcterms:creator": creator(),
+		});
+		import(&json!({ "@context": context(), "@graph": [vec![unit], nodes].concat() }))
+	}
+
+	#[test]
+	fn import_creates_genesis_records_and_creator_balances() {
+		let genesis = import_graph(vec![
+			concept("urn:test:apples", "urn:test:products", Some("urn:test:fruit")),
+			concept("urn:test:fruit", "urn:test:products", None),
+			scheme("urn:test:products"),
+			resource_spec("apple", &["urn:test:apples"]),
+		])
+		.unwrap();
+
+		let agents = genesis.valueflows_agent;
+		assert_eq!(agents.agents, vec![alice()]);
+		assert_eq!(agents.units, vec![(alice(), b"kilogram".to_vec(), b"kg".to_vec())]);
+		// Broader concepts are created first.
+		assert_eq!(
+			agents.concepts,
+			vec![
+				(ConceptSchemeId(0), b"urn:test:fruit".to_vec(), None),
+				(ConceptSchemeId(0), b"urn:test:apples".to_vec(), Some(ConceptId(0))),
+			]
+		);
+		assert_eq!(
+			agents.resource_specifications,
+			vec![(
+				alice(),
+				b"apple".to_vec(),
+				Some(b"ripe".to_vec()),
+				vec![ConceptId(1)],
+				Some(UnitId(0)),
+				None,
+			)]
+		);
+
+		// Five records, each with a deposit of at least the base deposit and the overhead.
+		let per_record = <Runtime as agent::Config>::DepositBase::get() +
+			<Runtime as agent::Config>::DepositPerByte::get() * RECORD_OVERHEAD as Balance;
+		let balances = genesis.balances.balances;
+		assert_eq!(balances.len(), 1);
+		assert_eq!(balances[0].0, alice());
+		assert!(balances[0].1 > EXISTENTIAL_DEPOSIT + 5 * per_record);
+	}
+
+	#[test]
+	fn import_rejects_values_exceeding_runtime_bounds() {
+		let name = "x".repeat(MaxNameLength::get() as usize + 1);
+		let result = import_graph(vec![resource_spec(&name, &[])]);
+		assert!(result.unwrap_err().contains("vf:name of \"urn:test:apple\" is longer than"));
+
+		let concepts: Vec<_> = (0..=MaxArrayLength::get())
+			.map(|index| format!("urn:test:concept-{}", index))
+			.collect();
+		let mut nodes: Vec<_> =
+			concepts.iter().map(|id| concept(id, "urn:test:products", None)).collect();
+		nodes.push(scheme("urn:test:products"));
+		let references: Vec<_> = concepts.iter().map(String::as_str).collect();
+		nodes.push(resource_spec("apple", &references));
+		let result = import_graph(nodes);
+		assert!(result.unwrap_err().contains("vf:resourceClassifiedAs"));
+	}
+
+	#[test]
+	fn import_rejects_broader_concepts_of_other_schemes() {
+		let result = import_graph(vec![
+			scheme("urn:test:products"),
+			scheme("urn:test:services"),
+			concept("urn:test:fruit", "urn:test:products", None),
+			concept("urn:test:delivery", "urn:test:services", Some("urn:test:fruit")),
+		]);
+		assert_eq!(
+			result.unwrap_err(),
+			"urn:test:delivery is not in the scheme of its broader concept"
+		);
+	}
+
+	#[test]
+	fn import_archives_archived_specifications() {
+		let mut archived = resource_spec("apple", &[]);
+		archived["vfs:archivedAt"] = json!(5);
+		let genesis = import_graph(vec![
+			json!({
+				"@type": "vf:ProcessSpecification",
+				"@id": "urn:test:picking",
+				"vf:name": "picking",
+				"dcterms:creator": creator(),
+			}),
+			json!({
+				"@type": "vf:ProcessSpecification",
+				"@id": "urn:test:sorting",
+				"vf:name": "sorting",
+				"vfs:archivedAt": 5,
+				"dcterms:creator": creator(),
+			}),
+			archived,
+		])
+		.unwrap();
+
+		let agents = genesis.valueflows_agent;
+		assert_eq!(agents.process_specifications.len(), 2);
+		assert_eq!(agents.archived_process_specifications, vec![ProcessSpecificationId(1)]);
+		assert_eq!(agents.archived_resource_specifications, vec![ResourceSpecificationId(0)]);
+	}
+
+	#[test]
+	fn import_rejects_images() {
+		let mut apple = resource_spec("apple", &[]);
+		apple["vf:image"] = json!(["ipfs://bafkreiapple"]);
+		let result = import_graph(vec![apple]);
+		assert!(result.unwrap_err().contains("has images"));
+
+		// An empty list of images is the same as none.
+		let mut apple = resource_spec("apple", &[]);
+		apple["vf:image"] = json!([]);
+		assert!(import_graph(vec![apple]).is_ok());
+	}
+	#[test]
+	fn import_expands_other_contexts_and_agent_ids() {
+		let address = alice().to_ss58check();
+		let genesis = import(&json!({
+			"@context": {
+				"@vocab": "http://www.ontology-of-units-of-measure.org/resource/om-2/",
+				"label": "http://www.w3.org/2000/01/rdf-schema#label",
+				"creator": { "@id": "http://purl.org/dc/terms/creator", "@type": "@id" },
+			},
+			"@graph": [{
+				"@type": "Unit",
+				"@id": "https://example.com/units/kg",
+				"label": "kilogram",
+				"symbol": "kg",
+				"creator": format!("https://example.com/agents/{}", address),
+			}],
+		}))
+		.unwrap();
+
+		let agents = genesis.valueflows_agent;
+		assert_eq!(agents.agents, vec![alice()]);
+		assert_eq!(agents.units, vec![(alice(), b"kilogram".to_vec(), b"kg".to_vec())]);
+	}
+
+	fn chain_spec(balances: Value, valueflows_agent: Value) -> Value {
+		json!({
+			"name": "Test",
+			"genesis": {
+				"runtime": {
+					"balances": { "balances": balances },
+					"valueflowsAgent": valueflows_agent,
+				},
+			},
+		})
+	}
+
+	#[test]
+	fn merge_adds_deposits_to_existing_endowments() {
+		let imported = import_graph(vec![]).unwrap();
+		let deposits = imported.balances.balances[0].1;
+		let bob = AccountId::new([2; 32]);
+		let existing = ValueflowsAgentConfig { agents: vec![bob.clone()], ..Default::default() };
+		let mut spec = chain_spec(
+			json!([[alice().to_ss58check(), 100], [bob.to_ss58check(), 100]]),
+			serde_json::to_value(existing).unwrap(),
+		);
+
+		merge(&mut spec, imported).unwrap();
+
+		let runtime = &spec["genesis"]["runtime"];
+		let balances: Vec<(AccountId, Balance)> =
+			serde_json::from_value(runtime["balances"]["balances"].clone()).unwrap();
+		assert_eq!(balances, vec![(alice(), 100 + deposits), (bob.clone(), 100)]);
+		let genesis: ValueflowsAgentConfig =
+			serde_json::from_value(runtime["valueflowsAgent"].clone()).unwrap();
+		assert_eq!(genesis.agents, vec![bob, alice()]);
+		assert_eq!(genesis.units.len(), 1);
+	}
+
+	#[test]
+	fn merge_rejects_chain_specs_with_records() {
+		let existing = ValueflowsAgentConfig {
+			units: vec![(alice(), b"metre".to_vec(), b"m".to_vec())],
+			..Default::default()
+		};
+		let mut spec = chain_spec(json!([]), serde_json::to_value(existing).unwrap());
+		assert_eq!(
+			merge(&mut spec, import_graph(vec![]).unwrap()).unwrap_err(),
+			"The chain spec already has agent pallet records"
+		);
+
+		let mut raw = json!({ "genesis": { "raw": {} } });
+		assert!(merge(&mut raw, import_graph(vec![]).unwrap()).is_err());
+	}
+}
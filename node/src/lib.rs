@@ -1,3 +1,7 @@
 pub mod chain_spec;
+pub mod content;
+pub mod graphql;
+pub mod indexer;
 pub mod rpc;
 pub mod service;
+pub mod storage;
@@ -7,7 +7,12 @@ mod service;
 mod benchmarking;
 mod cli;
 mod command;
+mod content;
+mod graphql;
+mod indexer;
+mod jsonld;
 mod rpc;
+mod storage;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
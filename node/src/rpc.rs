@@ -14,6 +14,8 @@ use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 
+use crate::{content::ContentStore, indexer};
+
 pub use sc_rpc_api::DenyUnsafe;
 
 /// Full client dependencies.
@@ -24,6 +26,10 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Store for off-chain content referenced by CID.
+	pub content_store: Arc<ContentStore>,
+	/// Off-chain index of the agent pallet records.
+	pub index: Arc<indexer::Index>,
 }
 
 /// Instantiate all full RPC extensions.
@@ -37,16 +43,25 @@ where
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: pallet_valueflows_agent_rpc::AgentRuntimeApi<Block>,
 	P: TransactionPool + 'static,
 {
+	use crate::{
+		content::{Content, ContentApiServer},
+		indexer::{IndexApiServer, IndexRpc},
+	};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+	use pallet_valueflows_agent_rpc::{Agent, AgentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, deny_unsafe, content_store, index } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Agent::new(client).into_rpc())?;
+	module.merge(Content::new(content_store, deny_unsafe).into_rpc())?;
+	module.merge(IndexRpc::new(index).into_rpc())?;
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
@@ -155,7 +155,10 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+	mut config: Configuration,
+	graphql: Option<crate::graphql::Config>,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -219,13 +222,56 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	let config_dir = config
+		.base_path
+		.as_ref()
+		.ok_or_else(|| ServiceError::Other("Content store and index require a base path".into()))?
+		.config_dir(config.chain_spec.id());
+	let content_store = {
+		let path = config_dir.join("content");
+		Arc::new(crate::content::ContentStore::open(&path).map_err(|e| {
+			ServiceError::Other(format!("Error opening content store at {:?}: {}", path, e))
+		})?)
+	};
+
+	let index = {
+		let path = config_dir.join("index.sqlite");
+		Arc::new(crate::indexer::Index::open(&path).map_err(|e| {
+			ServiceError::Other(format!("Error opening index at {:?}: {}", path, e))
+		})?)
+	};
+	task_manager.spawn_handle().spawn(
+		"valueflows-indexer",
+		None,
+		crate::indexer::run(client.clone(), index.clone()),
+	);
+
+	if let Some(graphql) = graphql {
+		let schema =
+			crate::graphql::schema(client.clone(), transaction_pool.clone(), graphql.signer);
+		let access = crate::graphql::Access {
+			token: graphql.token,
+			allowed_origins: graphql.allowed_origins,
+		};
+		task_manager.spawn_handle().spawn(
+			"valueflows-graphql",
+			None,
+			crate::graphql::serve(([127, 0, 0, 1], graphql.port).into(), schema, access),
+		);
+	}
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				content_store: content_store.clone(),
+				index: index.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
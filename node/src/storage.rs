@@ -0,0 +1,90 @@
+//! Reading the agent pallet storage from the client, for node services that serve or export it.
+
+use codec::{Decode, Encode};
+use node_template_runtime::opaque::Block;
+use pallet_valueflows_agent_rpc::AgentRuntimeApi;
+use sc_client_api::StorageProvider;
+use sp_api::ProvideRuntimeApi;
+use sp_core::{
+	hashing::{blake2_128, twox_64},
+	storage::StorageKey,
+	H256,
+};
+use sp_runtime::traits::Block as BlockT;
+
+use crate::{indexer::storage_prefix, service::FullClient};
+
+/// Error reading the agent pallet storage.
+pub(crate) type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Hasher of the keys of an agent pallet storage map.
+#[derive(Clone, Copy)]
+pub(crate) enum Hasher {
+	Twox64Concat,
+	Blake2_128Concat,
+}
+
+impl Hasher {
+	fn hash(self, key: &[u8]) -> Vec<u8> {
+		match self {
+			Hasher::Twox64Concat => [&twox_64(key)[..], key].concat(),
+			Hasher::Blake2_128Concat => [&blake2_128(key)[..], key].concat(),
+		}
+	}
+
+	fn len(self) -> usize {
+		match self {
+			Hasher::Twox64Concat => 8,
+			Hasher::Blake2_128Concat => 16,
+		}
+	}
+}
+
+/// The agent pallet storage at a block.
+pub(crate) struct AgentStorage<'a> {
+	client: &'a FullClient,
+	at: <Block as BlockT>::Hash,
+}
+
+impl<'a> AgentStorage<'a> {
+	/// The storage of `client` at the block `at`.
+	pub fn new(client: &'a FullClient, at: <Block as BlockT>::Hash) -> Self {
+		Self { client, at }
+	}
+
+	/// The value stored under `key` in the `item` map.
+	pub fn value<K: Encode, V: Decode>(
+		&self,
+		item: &str,
+		hasher: Hasher,
+		key: K,
+	) -> Result<Option<V>, Error> {
+		let key = [storage_prefix("ValueflowsAgent", item), hasher.hash(&key.encode())].concat();
+		let value = self.client.storage(self.at, &StorageKey(key))?;
+		Ok(value.map(|value| V::decode(&mut &value.0[..])).transpose()?)
+	}
+
+	/// All the entries of the `item` map.
+	pub fn entries<K: Decode, V: Decode>(
+		&self,
+		item: &str,
+		hasher: Hasher,
+	) -> Result<Vec<(K, V)>, Error> {
+		let prefix = storage_prefix("ValueflowsAgent", item);
+		self.client
+			.storage_pairs(self.at, &StorageKey(prefix.clone()))?
+			.into_iter()
+			.map(|(key, value)| {
+				let mut key = key.0.get(prefix.len() + hasher.len()..).unwrap_or_default();
+				Ok((K::decode(&mut key)?, V::decode(&mut &value.0[..])?))
+			})
+			.collect()
+	}
+
+	/// The text noted under `hash`.
+	pub fn text(&self, hash: Option<H256>) -> Result<Option<String>, Error> {
+		let Some(hash) = hash else { return Ok(None) };
+		let text = self.client.runtime_api().text(self.at, hash)?;
+		Ok(text.map(|text| String::from_utf8_lossy(&text).into_owned()))
+	}
+}
@@ -0,0 +1,19 @@
+//! Runtime API definition for valueflows agent pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use pallet_valueflows_agent::{ConceptId, ResourceSpecificationId};
+use sp_core::H256;
+use sp_std::prelude::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait AgentRuntimeApi {
+		/// The long-form text, such as a note, stored under `hash`.
+		fn text(hash: H256) -> Option<Vec<u8>>;
+
+		/// Resource specifications classified under `concept_id` or any of its narrower concepts.
+		fn resource_specifications_classified_under(
+			concept_id: ConceptId,
+		) -> Vec<ResourceSpecificationId>;
+	}
+}
@@ -0,0 +1,84 @@
+//! RPC interface for the valueflows agent pallet.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_valueflows_agent::{ConceptId, ResourceSpecificationId};
+pub use pallet_valueflows_agent_rpc_runtime_api::AgentRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use sp_runtime::traits::Block as BlockT;
+
+#[rpc(client, server)]
+pub trait AgentApi<BlockHash> {
+	/// The long-form text, such as a note or an address, stored under `hash`.
+	#[method(name = "vf_text")]
+	fn text(&self, hash: H256, at: Option<BlockHash>) -> RpcResult<Option<Bytes>>;
+
+	/// Resource specifications classified under a concept or any of its narrower concepts.
+	#[method(name = "vf_resourceSpecificationsClassifiedUnder")]
+	fn resource_specifications_classified_under(
+		&self,
+		concept_id: ConceptId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<ResourceSpecificationId>>;
+}
+
+pub struct Agent<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Agent<C, B> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+const RUNTIME_ERROR: i32 = 8000;
+
+#[async_trait]
+impl<C, Block> AgentApiServer<<Block as BlockT>::Hash> for Agent<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync,
+	C::Api: AgentRuntimeApi<Block>,
+{
+	fn text(&self, hash: H256, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		// If the block hash is not supplied assume the best block.
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let text = api.text(at, hash).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				RUNTIME_ERROR,
+				"Unable to query text.",
+				Some(e.to_string()),
+			))
+		})?;
+
+		Ok(text.map(Into::into))
+	}
+	fn resource_specifications_classified_under(
+		&self,
+		concept_id: ConceptId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<ResourceSpecificationId>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.resource_specifications_classified_under(at, concept_id).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				RUNTIME_ERROR,
+				"Unable to query classified resource specifications.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+}
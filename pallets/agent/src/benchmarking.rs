@@ -1,8 +1,455 @@
-//! Benchmarking setup for pallet-template
+//! Benchmarking setup for pallet-valueflows-agent
 
 use super::*;
 
 #[allow(unused)]
-use crate::Pallet as Template;
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use crate::Pallet as ValueflowsAgent;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller, BenchmarkError};
+use frame_support::{
+	traits::{Currency, EnsureOrigin, Get, StorePreimage},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
+use sp_core::H256;
+use sp_runtime::{traits::Bounded, FixedI64};
+use sp_std::{vec, vec::Vec};
+
+fn funded_agent<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	Agents::<T>::insert(&caller, true);
+	caller
+}
+
+/// A funded account that applied for registration in `mode`.
+fn applicant<T: Config>(mode: RegistrationMode) -> Result<T::AccountId, &'static str> {
+	let applicant: T::AccountId = account("applicant", 0, 0);
+	T::Currency::make_free_balance_be(&applicant, BalanceOf::<T>::max_value() / 2u32.into());
+	AgentRegistrationMode::<T>::put(mode);
+	ValueflowsAgent::<T>::register_agent(RawOrigin::Signed(applicant.clone()).into())?;
+	Ok(applicant)
+}
+
+fn string<S: Get<u32>>(len: u32) -> BoundedVec<u8, S> {
+	vec![b'x'; len.min(S::get()) as usize].try_into().unwrap()
+}
+
+/// Note a long text and return its hash. `seed` keeps texts of one record distinct.
+fn text<T: Config>(seed: u8) -> Option<H256> {
+	Some(T::Preimages::note(vec![seed; 1024].into()).unwrap())
+}
+
+fn images<T: Config>(len: u32) -> BoundedVec<Image<T>, T::MaxArrayLength> {
+	// The longest CID accepted, with the multi-byte code of blake2b-256.
+	let cid = crate::cid::cid_of(&[], crate::cid::Hasher::Blake2b256);
+	let image = Image::<T> {
+		cid: cid.try_into().unwrap(),
+		mime_type: string::<T::MaxMimeTypeLength>(u32::MAX),
+		size: crate::cid::MAX_CONTENT_SIZE,
+	};
+	vec![image; len as usize].try_into().unwrap()
+}
+
+/// Create a concept scheme of `owner` holding `len` concepts and return the concepts.
+fn concepts<T: Config>(owner: &T::AccountId, len: u32) -> BoundedVec<ConceptId, T::MaxArrayLength> {
+	let scheme_id =
+		ValueflowsAgent::<T>::do_create_concept_scheme(owner.clone(), string(1)).unwrap();
+	(0..len)
+		.map(|_| {
+			ValueflowsAgent::<T>::do_create_concept(owner.clone(), scheme_id, string(1), None)
+				.unwrap()
+		})
+		.collect::<Vec<_>>()
+		.try_into()
+		.unwrap()
+}
+
+/// Create a unit of `owner`, to be the default unit of resource specifications.
+fn unit<T: Config>(owner: &T::AccountId) -> Option<UnitId> {
+	Some(ValueflowsAgent::<T>::do_create_unit(owner.clone(), string(1), string(1)).unwrap())
+}
+
+/// Create `count` resource specifications of `owner` with every field at its longest, the
+/// heaviest records to archive or move. Their concepts and unit belong to another account, so they
+/// do not count towards the records of `owner`.
+fn resource_specifications<T: Config>(
+	owner: &T::AccountId,
+	count: u32,
+) -> Result<(), &'static str> {
+	let shared_owner: T::AccountId = account("concepts", 0, 0);
+	T::Currency::make_free_balance_be(&shared_owner, BalanceOf::<T>::max_value() / 2u32.into());
+	let concepts = concepts::<T>(&shared_owner, T::MaxArrayLength::get());
+	let unit = unit::<T>(&shared_owner);
+	for _ in 0..count {
+		ValueflowsAgent::<T>::create_resource_specification(
+			RawOrigin::Signed(owner.clone()).into(),
+			string(T::MaxNameLength::get()),
+			images::<T>(T::MaxArrayLength::get()),
+			text::<T>(0),
+			concepts.clone(),
+			unit,
+			unit,
+		)?;
+	}
+	Ok(())
+}
+
+fn coordinate() -> Option<FixedI64> {
+	Some(FixedI64::from_inner(1_234_567_890))
+}
+
+benchmarks! {
+	get_my_agent_pubkey {
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller))
+
+	register_agent {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		AgentRegistrationMode::<T>::put(RegistrationMode::Approved);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Applications::<T>::contains_key(&caller));
+	}
+
+	create_unit {
+		let s in 1 .. T::MaxNameLength::get();
+		let caller = funded_agent::<T>();
+	}: _(RawOrigin::Signed(caller), string(s), string(s))
+	verify {
+		assert!(Units::<T>::contains_key(UnitId(0)));
+	}
+
+	update_unit {
+		let s in 1 .. T::MaxNameLength::get();
+		let caller = funded_agent::<T>();
+		ValueflowsAgent::<T>::create_unit(
+			RawOrigin::Signed(caller.clone()).into(),
+			string(1),
+			string(1),
+		)?;
+	}: _(RawOrigin::Signed(caller), UnitId(0), string(s), string(s))
+	verify {
+		assert_eq!(Units::<T>::get(UnitId(0)).unwrap().label.len() as u32, s);
+	}
+
+	delete_unit {
+		let caller = funded_agent::<T>();
+		let max = T::MaxNameLength::get();
+		ValueflowsAgent::<T>::create_unit(
+			RawOrigin::Signed(caller.clone()).into(),
+			string(max),
+			string(max),
+		)?;
+	}: _(RawOrigin::Signed(caller), UnitId(0))
+	verify {
+		assert!(!Units::<T>::contains_key(UnitId(0)));
+	}
+
+	create_spatial_thing {
+		let s in 1 .. T::MaxNameLength::get();
+		let caller = funded_agent::<T>();
+	}: _(
+		RawOrigin::Signed(caller),
+		string(s),
+		text::<T>(0),
+		text::<T>(1),
+		coordinate(),
+		coordinate(),
+		coordinate()
+	)
+	verify {
+		assert!(SpatialThings::<T>::contains_key(SpatialThingId(0)));
+	}
+
+	update_spatial_thing {
+		let s in 1 .. T::MaxNameLength::get();
+		let caller = funded_agent::<T>();
+		ValueflowsAgent::<T>::create_spatial_thing(
+			RawOrigin::Signed(caller.clone()).into(),
+			string(1),
+			text::<T>(0),
+			text::<T>(1),
+			None,
+			None,
+			None,
+		)?;
+	}: _(
+		RawOrigin::Signed(caller),
+		SpatialThingId(0),
+		string(s),
+		text::<T>(2),
+		text::<T>(3),
+		coordinate(),
+		coordinate(),
+		coordinate()
+	)
+	verify {
+		assert_eq!(SpatialThings::<T>::get(SpatialThingId(0)).unwrap().name.len() as u32, s);
+	}
+
+	delete_spatial_thing {
+		let caller = funded_agent::<T>();
+		let max = T::MaxNameLength::get();
+		ValueflowsAgent::<T>::create_spatial_thing(
+			RawOrigin::Signed(caller.clone()).into(),
+			string(max),
+			text::<T>(0),
+			text::<T>(1),
+			coordinate(),
+			coordinate(),
+			coordinate(),
+		)?;
+	}: _(RawOrigin::Signed(caller), SpatialThingId(0))
+	verify {
+		assert!(!SpatialThings::<T>::contains_key(SpatialThingId(0)));
+	}
+
+	create_process_specification {
+		let s in 1 .. T::MaxNameLength::get();
+		let caller = funded_agent::<T>();
+	}: _(RawOrigin::Signed(caller), string(s), text::<T>(0))
+	verify {
+		assert!(ProcessSpecifications::<T>::contains_key(ProcessSpecificationId(0)));
+	}
+
+	update_process_specification {
+		let s in 1 .. T::MaxNameLength::get();
+		let caller = funded_agent::<T>();
+		ValueflowsAgent::<T>::create_process_specification(
+			RawOrigin::Signed(caller.clone()).into(),
+			string(1),
+			text::<T>(0),
+		)?;
+	}: _(
+		RawOrigin::Signed(caller),
+		ProcessSpecificationId(0),
+		string(s),
+		text::<T>(1)
+	)
+	verify {
+		let process_spec = ProcessSpecifications::<T>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!(process_spec.name.len() as u32, s);
+	}
+
+	archive_process_specification {
+		let caller = funded_agent::<T>();
+		let max = T::MaxNameLength::get();
+		ValueflowsAgent::<T>::create_process_specification(
+			RawOrigin::Signed(caller.clone()).into(),
+			string(max),
+			text::<T>(0),
+		)?;
+	}: _(RawOrigin::Signed(caller), ProcessSpecificationId(0))
+	verify {
+		let process_spec = ProcessSpecifications::<T>::get(ProcessSpecificationId(0)).unwrap();
+		assert!(process_spec.archived_at.is_some());
+	}
+
+	create_resource_specification {
+		let s in 1 .. T::MaxNameLength::get();
+		let a in 0 .. T::MaxArrayLength::get();
+		let caller = funded_agent::<T>();
+		let concepts = concepts::<T>(&caller, a);
+		let unit = unit::<T>(&caller);
+	}: _(
+		RawOrigin::Signed(caller),
+		string(s),
+		images::<T>(a),
+		text::<T>(0),
+		concepts,
+		unit,
+		unit
+	)
+	verify {
+		assert!(ResourceSpecifications::<T>::contains_key(ResourceSpecificationId(0)));
+	}
+
+	update_resource_specification {
+		let s in 1 .. T::MaxNameLength::get();
+		let a in 0 .. T::MaxArrayLength::get();
+		let caller = funded_agent::<T>();
+		let old_concepts = concepts::<T>(&caller, a);
+		let new_concepts = concepts::<T>(&caller, a);
+		let unit = unit::<T>(&caller);
+		ValueflowsAgent::<T>::create_resource_specification(
+			RawOrigin::Signed(caller.clone()).into(),
+			string(1),
+			images::<T>(0),
+			text::<T>(0),
+			old_concepts,
+			None,
+			None,
+		)?;
+	}: _(
+		RawOrigin::Signed(caller),
+		ResourceSpecificationId(0),
+		string(s),
+		images::<T>(a),
+		text::<T>(1),
+		new_concepts,
+		unit,
+		unit
+	)
+	verify {
+		let resource_spec = ResourceSpecifications::<T>::get(ResourceSpecificationId(0)).unwrap();
+		assert_eq!(resource_spec.images.len() as u32, a);
+	}
+
+	archive_resource_specification {
+		let caller = funded_agent::<T>();
+		let max = T::MaxNameLength::get();
+		let max_array = T::MaxArrayLength::get();
+		let concepts = concepts::<T>(&caller, max_array);
+		let unit = unit::<T>(&caller);
+		ValueflowsAgent::<T>::create_resource_specification(
+			RawOrigin::Signed(caller.clone()).into(),
+			string(max),
+			images::<T>(max_array),
+			text::<T>(0),
+			concepts,
+			unit,
+			unit,
+		)?;
+	}: _(RawOrigin::Signed(caller), ResourceSpecificationId(0))
+	verify {
+		let resource_spec = ResourceSpecifications::<T>::get(ResourceSpecificationId(0)).unwrap();
+		assert!(resource_spec.archived_at.is_some());
+	}
+
+	create_concept_scheme {
+		let s in 1 .. T::MaxNameLength::get();
+		let caller = funded_agent::<T>();
+	}: _(RawOrigin::Signed(caller), string(s))
+	verify {
+		assert!(ConceptSchemes::<T>::contains_key(ConceptSchemeId(0)));
+	}
+
+	create_concept {
+		let s in 1 .. T::MaxNameLength::get();
+		let caller = funded_agent::<T>();
+		let broader = concepts::<T>(&caller, 1)[0];
+	}: _(RawOrigin::Signed(caller), ConceptSchemeId(0), string(s), Some(broader))
+	verify {
+		assert!(NarrowerConcepts::<T>::contains_key(broader, ConceptId(1)));
+	}
+
+	delete_concept {
+		let caller = funded_agent::<T>();
+		let broader = concepts::<T>(&caller, 1)[0];
+		ValueflowsAgent::<T>::create_concept(
+			RawOrigin::Signed(caller.clone()).into(),
+			ConceptSchemeId(0),
+			string(T::MaxNameLength::get()),
+			Some(broader),
+		)?;
+	}: _(RawOrigin::Signed(caller), ConceptId(1))
+	verify {
+		assert!(!Concepts::<T>::contains_key(ConceptId(1)));
+	}
+
+	deregister_agent_spatial_things {
+		let r in 0 .. T::MaxOwnedRecords::get();
+		let caller = funded_agent::<T>();
+		for _ in 0 .. r {
+			ValueflowsAgent::<T>::create_spatial_thing(
+				RawOrigin::Signed(caller.clone()).into(),
+				string(T::MaxNameLength::get()),
+				text::<T>(0),
+				text::<T>(1),
+				coordinate(),
+				coordinate(),
+				coordinate(),
+			)?;
+		}
+	}: deregister_agent(RawOrigin::Signed(caller.clone()), r)
+	verify {
+		assert!(!Agents::<T>::contains_key(&caller));
+		assert!(SpatialThings::<T>::iter_keys().next().is_none());
+	}
+
+	deregister_agent_resource_specifications {
+		let r in 0 .. T::MaxOwnedRecords::get();
+		let caller = funded_agent::<T>();
+		resource_specifications::<T>(&caller, r)?;
+	}: deregister_agent(RawOrigin::Signed(caller.clone()), r)
+	verify {
+		assert!(!Agents::<T>::contains_key(&caller));
+		assert!(ResourceSpecifications::<T>::iter_values().all(|spec| spec.archived_at.is_some()));
+	}
+
+	initiate_key_rotation {
+		let caller = funded_agent::<T>();
+		let new: T::AccountId = account("new", 0, 0);
+	}: _(RawOrigin::Signed(caller.clone()), new.clone())
+	verify {
+		assert_eq!(KeyRotations::<T>::get(&caller), Some(new));
+	}
+
+	accept_key_rotation {
+		let r in 0 .. T::MaxOwnedRecords::get();
+		let old = funded_agent::<T>();
+		let new: T::AccountId = account("new", 0, 0);
+		T::Currency::make_free_balance_be(&new, BalanceOf::<T>::max_value() / 2u32.into());
+		resource_specifications::<T>(&old, r)?;
+		ValueflowsAgent::<T>::initiate_key_rotation(
+			RawOrigin::Signed(old.clone()).into(),
+			new.clone(),
+		)?;
+	}: _(RawOrigin::Signed(new.clone()), old.clone(), r)
+	verify {
+		assert!(Agents::<T>::contains_key(&new));
+		assert_eq!(OwnedRecords::<T>::iter_key_prefix(&new).count() as u32, r);
+	}
+
+	set_registration_mode {
+		let origin =
+			T::RegistrationOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let mode = RegistrationMode::Vouched(T::MaxVouchers::get());
+	}: _<T::RuntimeOrigin>(origin, mode)
+	verify {
+		assert_eq!(AgentRegistrationMode::<T>::get(), mode);
+	}
+
+	approve_application {
+		let origin =
+			T::RegistrationOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let applicant = applicant::<T>(RegistrationMode::Approved)?;
+	}: _<T::RuntimeOrigin>(origin, applicant.clone())
+	verify {
+		assert!(Agents::<T>::contains_key(&applicant));
+	}
+
+	reject_application {
+		let origin =
+			T::RegistrationOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let applicant = applicant::<T>(RegistrationMode::Approved)?;
+	}: _<T::RuntimeOrigin>(origin, applicant.clone())
+	verify {
+		assert!(!Applications::<T>::contains_key(&applicant));
+	}
+
+	withdraw_application {
+		let applicant = applicant::<T>(RegistrationMode::Approved)?;
+	}: _(RawOrigin::Signed(applicant.clone()))
+	verify {
+		assert!(!Applications::<T>::contains_key(&applicant));
+	}
+
+	// The last voucher registers the applicant.
+	vouch {
+		let required = T::MaxVouchers::get();
+		let applicant = applicant::<T>(RegistrationMode::Vouched(required))?;
+		for i in 1 .. required {
+			let voucher: T::AccountId = account("voucher", i, 0);
+			Agents::<T>::insert(&voucher, true);
+			ValueflowsAgent::<T>::vouch(RawOrigin::Signed(voucher).into(), applicant.clone())?;
+		}
+		let caller = funded_agent::<T>();
+	}: _(RawOrigin::Signed(caller), applicant.clone())
+	verify {
+		assert!(Agents::<T>::contains_key(&applicant));
+	}
+
+	impl_benchmark_test_suite!(ValueflowsAgent, crate::mock::new_test_ext(), crate::mock::Test);
+}
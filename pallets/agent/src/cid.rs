@@ -0,0 +1,70 @@
+//! Content identifiers (CIDs) of off-chain content referenced by records, such as images.
+//!
+//! The pallet and the node's content store both parse CIDs with [`parse`], so every CID accepted
+//! on chain can be stored by the node and the other way around.
+
+use sp_core::hashing::{blake2_256, sha2_256};
+use sp_std::{vec, vec::Vec};
+
+/// The largest piece of content that can be referenced by CID, in bytes.
+///
+/// Content is uploaded to the node hex-encoded over RPC, which doubles its size, so this is kept
+/// below half of the default 15 MiB limit of `--rpc-max-request-size`.
+pub const MAX_CONTENT_SIZE: u32 = 7 * 1024 * 1024;
+
+const CID_V1: u8 = 0x01;
+const RAW_CODEC: u8 = 0x55;
+const SHA2_256: &[u8] = &[0x12];
+const BLAKE2B_256: &[u8] = &[0xa0, 0xe4, 0x02];
+const DIGEST_LENGTH: u8 = 0x20;
+
+/// A hash function supported in CIDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hasher {
+	/// sha2-256, used by CIDv0 and most IPFS tooling.
+	Sha2_256,
+	/// blake2b-256, the hash used on chain.
+	Blake2b256,
+}
+
+impl Hasher {
+	fn code(self) -> &'static [u8] {
+		match self {
+			Hasher::Sha2_256 => SHA2_256,
+			Hasher::Blake2b256 => BLAKE2B_256,
+		}
+	}
+
+	/// The digest of `content`.
+	pub fn digest(self, content: &[u8]) -> [u8; 32] {
+		match self {
+			Hasher::Sha2_256 => sha2_256(content),
+			Hasher::Blake2b256 => blake2_256(content),
+		}
+	}
+}
+
+/// Build the CIDv1 of `content` with the raw codec.
+pub fn cid_of(content: &[u8], hasher: Hasher) -> Vec<u8> {
+	let mut cid = vec![CID_V1, RAW_CODEC];
+	cid.extend_from_slice(hasher.code());
+	cid.push(DIGEST_LENGTH);
+	cid.extend_from_slice(&hasher.digest(content));
+	cid
+}
+
+/// Split a binary CID into its hash function and digest.
+///
+/// Accepts CIDv0 and CIDv1 with a single byte codec, hashed with sha2-256 or blake2b-256 into a
+/// 32 byte digest.
+pub fn parse(cid: &[u8]) -> Option<(Hasher, &[u8])> {
+	let multihash = match cid {
+		[CID_V1, codec, multihash @ ..] if *codec < 0x80 => multihash,
+		[0x12, DIGEST_LENGTH, ..] => cid,
+		_ => return None,
+	};
+	[Hasher::Sha2_256, Hasher::Blake2b256].into_iter().find_map(|hasher| {
+		let digest = multihash.strip_prefix(hasher.code())?.strip_prefix(&[DIGEST_LENGTH])?;
+		(digest.len() == DIGEST_LENGTH as usize).then_some((hasher, digest))
+	})
+}
@@ -1,6 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode, MaxEncodedLen};
 pub use pallet::*;
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{traits::Hash, RuntimeDebug};
 
 #[cfg(test)]
 mod mock;
@@ -10,26 +15,196 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod cid;
+pub mod migrations;
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// Identifier of a record kept by this pallet.
+pub trait RecordId: Copy + Encode {
+	/// Prefix distinguishing this record type when deriving global identifiers.
+	const PREFIX: &'static [u8];
+
+	/// The identifier following this one, or `None` once the id space is exhausted.
+	fn next(self) -> Option<Self>;
+
+	/// The global id of this record on the chain with `genesis_hash`, see [`Pallet::global_id`].
+	/// Also used off chain, e.g. by exports, where the pallet storage is not at hand.
+	fn global_id<H: Hash>(self, genesis_hash: H::Output) -> H::Output {
+		H::hash_of(&(Self::PREFIX, genesis_hash, self))
+	}
+}
+
+macro_rules! record_id {
+	($(#[$attr:meta])* $name:ident, $prefix:literal) => {
+		$(#[$attr])*
+		#[derive(
+			Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Default, RuntimeDebug,
+			TypeInfo, MaxEncodedLen,
+		)]
+		#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+		pub struct $name(pub u32);
+
+		impl RecordId for $name {
+			const PREFIX: &'static [u8] = $prefix;
+
+			fn next(self) -> Option<Self> {
+				self.0.checked_add(1).map(Self)
+			}
+		}
+
+		impl From<u32> for $name {
+			fn from(id: u32) -> Self {
+				Self(id)
+			}
+		}
+	};
+}
+
+record_id!(
+	/// Identifier of a [`Unit`].
+	UnitId,
+	b"vf/unit"
+);
+record_id!(
+	/// Identifier of a [`SpatialThing`].
+	SpatialThingId,
+	b"vf/spatial_thing"
+);
+record_id!(
+	/// Identifier of a [`ProcessSpecification`].
+	ProcessSpecificationId,
+	b"vf/process_specification"
+);
+record_id!(
+	/// Identifier of a [`ResourceSpecification`].
+	ResourceSpecificationId,
+	b"vf/resource_specification"
+);
+record_id!(
+	/// Identifier of a [`ConceptScheme`].
+	ConceptSchemeId,
+	b"vf/concept_scheme"
+);
+record_id!(
+	/// Identifier of a [`Concept`].
+	ConceptId,
+	b"vf/concept"
+);
+
+/// A record referenced by an item of [`Pallet::batch_create`].
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum BatchRef<I> {
+	/// A record that already exists.
+	Existing(I),
+	/// The record created by the item at this index of the same batch. Only earlier items can
+	/// be referenced.
+	Batch(u32),
+}
+
+/// A record of any type, as created by [`Pallet::batch_create`] or owned by an agent.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum CreatedRecord {
+	Unit(UnitId),
+	SpatialThing(SpatialThingId),
+	ProcessSpecification(ProcessSpecificationId),
+	ResourceSpecification(ResourceSpecificationId),
+	ConceptScheme(ConceptSchemeId),
+	Concept(ConceptId),
+}
+
+/// How accounts become agents.
+#[derive(
+	Clone, Copy, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RegistrationMode {
+	/// Any account can register itself.
+	#[default]
+	Open,
+	/// Accounts apply and [`Config::RegistrationOrigin`] approves or rejects them.
+	Approved,
+	/// Accounts apply and are registered once this many agents vouched for them.
+	/// [`Config::RegistrationOrigin`] can still approve or reject them.
+	Vouched(u32),
+}
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::pallet_prelude::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, QueryPreimage, ReservableCurrency, StorePreimage},
+		weights::Weight,
+	};
 	use frame_system::pallet_prelude::*;
 	use scale_info::TypeInfo;
-	use sp_runtime::FixedI64;
+	use sp_core::H256;
+	use sp_runtime::{
+		traits::{Hash, Saturating, Zero},
+		FixedI64,
+	};
+
+	use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
+
+	use crate::{
+		weights::WeightInfo, BatchRef, ConceptId, ConceptSchemeId, CreatedRecord, Owned,
+		ProcessSpecificationId, RecordId, RegistrationMode, ResourceSpecificationId,
+		SpatialThingId, UnitId,
+	};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-		/// The maximum length of string.
+		/// The maximum length of a name or unit label.
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+		/// The maximum length of a unit symbol.
+		#[pallet::constant]
+		type MaxSymbolLength: Get<u32>;
+		/// The maximum length of an image MIME type.
 		#[pallet::constant]
-		type MaxStringLength: Get<u32>;
+		type MaxMimeTypeLength: Get<u32>;
 		#[pallet::constant]
 		type MaxArrayLength: Get<u32>;
+		/// The maximum length of a content identifier (CID) in bytes.
+		#[pallet::constant]
+		type MaxCidLength: Get<u32>;
+		/// Where long-form text such as notes and addresses is kept. Records only store the hash
+		/// of the text and request its preimage for as long as they reference it.
+		type Preimages: QueryPreimage + StorePreimage;
+		/// The currency in which storage deposits are reserved.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The base deposit reserved for every record an agent creates.
+		#[pallet::constant]
+		type DepositBase: Get<BalanceOf<Self>>;
+		/// The additional deposit reserved per byte of a stored record.
+		#[pallet::constant]
+		type DepositPerByte: Get<BalanceOf<Self>>;
+		/// The maximum number of records created by one [`Pallet::batch_create`] call.
+		#[pallet::constant]
+		type MaxBatchLength: Get<u32>;
+		/// The origin that sets the registration mode and approves or rejects applications.
+		type RegistrationOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The maximum number of agents that can vouch for an application.
+		#[pallet::constant]
+		type MaxVouchers: Get<u32>;
+		/// The maximum number of records an agent can own. It bounds the work of
+		/// [`Pallet::deregister_agent`] and [`Pallet::accept_key_rotation`].
+		#[pallet::constant]
+		type MaxOwnedRecords: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -40,195 +215,680 @@ pub mod pallet {
 		bool,
 	>;
 
+	/// How accounts become agents.
+	#[pallet::storage]
+	pub type AgentRegistrationMode<T> = StorageValue<_, RegistrationMode, ValueQuery>;
+
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Application<T: Config> {
+		/// Reserved until the application is decided, and slashed if it is rejected.
+		pub deposit: BalanceOf<T>,
+		/// The agents that vouched for the applicant.
+		pub vouchers: BoundedVec<T::AccountId, T::MaxVouchers>,
+	}
+
+	/// Pending applications for registration as an agent.
+	#[pallet::storage]
+	pub type Applications<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Application<T>,
+	>;
+
+	/// The records owned by an agent, keyed by `(owner, record)`.
+	#[pallet::storage]
+	pub type OwnedRecords<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		CreatedRecord,
+		(),
+	>;
+
+	/// The number of records owned by an agent.
+	#[pallet::storage]
+	pub type OwnedRecordCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Key rotations initiated by an agent, to the account that has to accept them.
+	#[pallet::storage]
+	pub type KeyRotations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		T::AccountId,
+	>;
+
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
 	pub struct Unit<T: Config> {
-		label: BoundedVec<u8, T::MaxStringLength>,
-		symbol: BoundedVec<u8, T::MaxStringLength>,
+		pub owner: T::AccountId,
+		pub deposit: BalanceOf<T>,
+		pub label: BoundedVec<u8, T::MaxNameLength>,
+		pub symbol: BoundedVec<u8, T::MaxSymbolLength>,
 	}
 
 	#[pallet::storage]
-	pub type UnitId<T> = StorageValue<_, u32, ValueQuery>;
+	#[pallet::storage_prefix = "UnitId"]
+	pub type NextUnitId<T> = StorageValue<_, UnitId, ValueQuery>;
 
 	#[pallet::storage]
 	pub type Units<T: Config> = StorageMap<
 		_,
 		Twox64Concat,
-		u32,
+		UnitId,
 		Unit<T>,
 	>;
 
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
 	pub struct SpatialThing<T: Config> {
-		name: BoundedVec<u8, T::MaxStringLength>,
-		note: Option<BoundedVec<u8, T::MaxStringLength>>,
-		mappable_address: Option<BoundedVec<u8, T::MaxStringLength>>,
-		lat: Option<FixedI64>,
-		long: Option<FixedI64>,
-		alt: Option<FixedI64>,
+		pub owner: T::AccountId,
+		pub deposit: BalanceOf<T>,
+		pub name: BoundedVec<u8, T::MaxNameLength>,
+		/// Hash of the note, see [`Config::Preimages`].
+		pub note: Option<H256>,
+		/// Hash of the mappable address, see [`Config::Preimages`].
+		pub mappable_address: Option<H256>,
+		pub lat: Option<FixedI64>,
+		pub long: Option<FixedI64>,
+		pub alt: Option<FixedI64>,
 	}
 
 	#[pallet::storage]
-	pub type SpatialThingId<T> = StorageValue<_, u32, ValueQuery>;
+	#[pallet::storage_prefix = "SpatialThingId"]
+	pub type NextSpatialThingId<T> = StorageValue<_, SpatialThingId, ValueQuery>;
 
 	#[pallet::storage]
 	pub type SpatialThings<T: Config> = StorageMap<
 		_,
 		Twox64Concat,
-		u32,
+		SpatialThingId,
 		SpatialThing<T>,
 	>;
 
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
 	pub struct ProcessSpecification<T: Config> {
-		name: BoundedVec<u8, T::MaxStringLength>,
-		note: Option<BoundedVec<u8, T::MaxStringLength>>,
+		pub owner: T::AccountId,
+		pub deposit: BalanceOf<T>,
+		pub name: BoundedVec<u8, T::MaxNameLength>,
+		/// Hash of the note, see [`Config::Preimages`].
+		pub note: Option<H256>,
+		/// Number of times the specification was updated or archived.
+		pub revision: u32,
+		/// Hash of the encoded previous revision, `None` for the first one.
+		pub previous_version: Option<T::Hash>,
+		/// Block the specification was archived in, if it was.
+		pub archived_at: Option<T::BlockNumber>,
 	}
 
 	#[pallet::storage]
-	pub type ProcessSpecificationId<T> = StorageValue<_, u32, ValueQuery>;
+	#[pallet::storage_prefix = "ProcessSpecificationId"]
+	pub type NextProcessSpecificationId<T> = StorageValue<_, ProcessSpecificationId, ValueQuery>;
 
 	#[pallet::storage]
 	pub type ProcessSpecifications<T: Config> = StorageMap<
 		_,
 		Twox64Concat,
-		u32,
+		ProcessSpecificationId,
 		ProcessSpecification<T>,
 	>;
 
-	// TODO use ipfs to store images
+	/// An image kept off-chain, e.g. in the node's content store or IPFS, and referenced by its
+	/// binary CID.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Image<T: Config> {
+		pub cid: BoundedVec<u8, T::MaxCidLength>,
+		pub mime_type: BoundedVec<u8, T::MaxMimeTypeLength>,
+		/// Size of the content in bytes, at most [`crate::cid::MAX_CONTENT_SIZE`].
+		pub size: u32,
+	}
+
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
 	pub struct ResourceSpecification<T: Config> {
-		name: BoundedVec<u8, T::MaxStringLength>,
-		images: BoundedVec<T::Hash, T::MaxArrayLength>,
-		note: Option<BoundedVec<u8, T::MaxStringLength>>,
-		resource_classified_as: BoundedVec<BoundedVec<u8, T::MaxStringLength>, T::MaxArrayLength>,
-		default_unit_of_resource_id: Option<u32>,
-		default_unit_of_effort_id: Option<u32>,
+		pub owner: T::AccountId,
+		pub deposit: BalanceOf<T>,
+		pub name: BoundedVec<u8, T::MaxNameLength>,
+		pub images: BoundedVec<Image<T>, T::MaxArrayLength>,
+		/// Hash of the note, see [`Config::Preimages`].
+		pub note: Option<H256>,
+		/// Concepts classifying the resource, see [`Concept`].
+		pub resource_classified_as: BoundedVec<ConceptId, T::MaxArrayLength>,
+		pub default_unit_of_resource_id: Option<UnitId>,
+		pub default_unit_of_effort_id: Option<UnitId>,
+		/// Number of times the specification was updated or archived.
+		pub revision: u32,
+		/// Hash of the encoded previous revision, `None` for the first one.
+		pub previous_version: Option<T::Hash>,
+		/// Block the specification was archived in, if it was.
+		pub archived_at: Option<T::BlockNumber>,
 	}
 
 	#[pallet::storage]
-	pub type ResourceSpecificationId<T> = StorageValue<_, u32, ValueQuery>;
+	#[pallet::storage_prefix = "ResourceSpecificationId"]
+	pub type NextResourceSpecificationId<T> = StorageValue<_, ResourceSpecificationId, ValueQuery>;
 
 	#[pallet::storage]
 	pub type ResourceSpecifications<T: Config> = StorageMap<
 		_,
 		Twox64Concat,
-		u32,
+		ResourceSpecificationId,
 		ResourceSpecification<T>,
 	>;
 
+	/// A classification scheme, such as a product taxonomy, grouping a hierarchy of concepts.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ConceptScheme<T: Config> {
+		pub owner: T::AccountId,
+		pub deposit: BalanceOf<T>,
+		pub name: BoundedVec<u8, T::MaxNameLength>,
+	}
+
+	#[pallet::storage]
+	#[pallet::storage_prefix = "ConceptSchemeId"]
+	pub type NextConceptSchemeId<T> = StorageValue<_, ConceptSchemeId, ValueQuery>;
+
+	#[pallet::storage]
+	pub type ConceptSchemes<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		ConceptSchemeId,
+		ConceptScheme<T>,
+	>;
+
+	/// A concept of a [`ConceptScheme`], optionally narrowing a broader concept of the same
+	/// scheme, in the manner of SKOS.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Concept<T: Config> {
+		pub owner: T::AccountId,
+		pub deposit: BalanceOf<T>,
+		pub scheme: ConceptSchemeId,
+		pub label: BoundedVec<u8, T::MaxNameLength>,
+		pub broader: Option<ConceptId>,
+	}
+
+	#[pallet::storage]
+	#[pallet::storage_prefix = "ConceptId"]
+	pub type NextConceptId<T> = StorageValue<_, ConceptId, ValueQuery>;
+
+	#[pallet::storage]
+	pub type Concepts<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		ConceptId,
+		Concept<T>,
+	>;
+
+	/// A record to create with [`Pallet::batch_create`]. Records created by earlier items of the
+	/// same batch are referenced with [`BatchRef::Batch`].
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub enum BatchItem<T: Config> {
+		Unit {
+			label: BoundedVec<u8, T::MaxNameLength>,
+			symbol: BoundedVec<u8, T::MaxSymbolLength>,
+		},
+		SpatialThing {
+			name: BoundedVec<u8, T::MaxNameLength>,
+			note: Option<H256>,
+			mappable_address: Option<H256>,
+			lat: Option<FixedI64>,
+			long: Option<FixedI64>,
+			alt: Option<FixedI64>,
+		},
+		ProcessSpecification {
+			name: BoundedVec<u8, T::MaxNameLength>,
+			note: Option<H256>,
+		},
+		ResourceSpecification {
+			name: BoundedVec<u8, T::MaxNameLength>,
+			images: BoundedVec<Image<T>, T::MaxArrayLength>,
+			note: Option<H256>,
+			resource_classified_as: BoundedVec<BatchRef<ConceptId>, T::MaxArrayLength>,
+			default_unit_of_resource_id: Option<BatchRef<UnitId>>,
+			default_unit_of_effort_id: Option<BatchRef<UnitId>>,
+		},
+		ConceptScheme {
+			name: BoundedVec<u8, T::MaxNameLength>,
+		},
+		Concept {
+			scheme: BatchRef<ConceptSchemeId>,
+			label: BoundedVec<u8, T::MaxNameLength>,
+			broader: Option<BatchRef<ConceptId>>,
+		},
+	}
+
+	/// The narrower concepts of a concept, keyed by `(broader, narrower)`.
+	#[pallet::storage]
+	pub type NarrowerConcepts<T> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ConceptId,
+		Twox64Concat,
+		ConceptId,
+		(),
+	>;
+
+	/// The resource specifications classified as a concept, keyed by
+	/// `(concept_id, resource_spec_id)`.
+	#[pallet::storage]
+	pub type ClassifiedResourceSpecifications<T> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ConceptId,
+		Twox64Concat,
+		ResourceSpecificationId,
+		(),
+	>;
+
+	/// The resource specifications referring to a unit as their default unit of resource or
+	/// effort, keyed by `(unit_id, resource_spec_id)`.
+	#[pallet::storage]
+	pub type UnitResourceSpecifications<T> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		UnitId,
+		Twox64Concat,
+		ResourceSpecificationId,
+		(),
+	>;
+
+	/// Spatial thing set up at genesis: `(owner, name, note, mappable_address, lat, long, alt)`.
+	pub type GenesisSpatialThing<AccountId> = (
+		AccountId,
+		Vec<u8>,
+		Option<Vec<u8>>,
+		Option<Vec<u8>>,
+		Option<FixedI64>,
+		Option<FixedI64>,
+		Option<FixedI64>,
+	);
+
+	/// Resource specification set up at genesis: `(owner, name, note, resource_classified_as,
+	/// default_unit_of_resource_id, default_unit_of_effort_id)`.
+	pub type GenesisResourceSpecification<AccountId> =
+		(AccountId, Vec<u8>, Option<Vec<u8>>, Vec<ConceptId>, Option<UnitId>, Option<UnitId>);
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Accounts registered as agents.
+		pub agents: Vec<T::AccountId>,
+		/// How further accounts become agents.
+		pub registration_mode: RegistrationMode,
+		/// Units as `(owner, label, symbol)`.
+		pub units: Vec<(T::AccountId, Vec<u8>, Vec<u8>)>,
+		/// Spatial things, see [`GenesisSpatialThing`].
+		pub spatial_things: Vec<GenesisSpatialThing<T::AccountId>>,
+		/// Process specifications as `(owner, name, note)`.
+		pub process_specifications: Vec<(T::AccountId, Vec<u8>, Option<Vec<u8>>)>,
+		/// Resource specifications, see [`GenesisResourceSpecification`].
+		pub resource_specifications: Vec<GenesisResourceSpecification<T::AccountId>>,
+		/// Concept schemes as `(owner, name)`.
+		pub concept_schemes: Vec<(T::AccountId, Vec<u8>)>,
+		/// Concepts as `(scheme, label, broader)`, owned by the owner of their scheme.
+		pub concepts: Vec<(ConceptSchemeId, Vec<u8>, Option<ConceptId>)>,
+		/// Process specifications archived once created, e.g. when they are imported.
+		pub archived_process_specifications: Vec<ProcessSpecificationId>,
+		/// Resource specifications archived once created.
+		pub archived_resource_specifications: Vec<ResourceSpecificationId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self {
+				agents: Default::default(),
+				registration_mode: Default::default(),
+				units: Default::default(),
+				spatial_things: Default::default(),
+				process_specifications: Default::default(),
+				resource_specifications: Default::default(),
+				concept_schemes: Default::default(),
+				concepts: Default::default(),
+				archived_process_specifications: Default::default(),
+				archived_resource_specifications: Default::default(),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for who in &self.agents {
+				Agents::<T>::insert(who, true);
+			}
+			assert!(
+				Pallet::<T>::valid_registration_mode(self.registration_mode),
+				"registration mode must require between one and MaxVouchers vouchers",
+			);
+			AgentRegistrationMode::<T>::put(self.registration_mode);
+
+			// Records are created the same way as through the extrinsics, so the owners must be
+			// registered agents with enough balance to cover the deposits.
+			let agent = |who: &T::AccountId| {
+				assert!(Agents::<T>::contains_key(who), "record owner must be a genesis agent");
+				who.clone()
+			};
+
+			// Genesis texts are noted without a deposit, which also requests them once. That
+			// request is dropped at the end, leaving only the ones held by the records.
+			let mut texts = BTreeSet::new();
+			let mut noted = |text: &Option<Vec<u8>>| {
+				text.as_ref().map(|text| {
+					let hash = T::Preimages::note(text.into()).expect("genesis text can be noted");
+					texts.insert(hash);
+					hash
+				})
+			};
+
+			for (owner, label, symbol) in &self.units {
+				Pallet::<T>::do_create_unit(agent(owner), bounded(label), bounded(symbol))
+					.expect("genesis unit can be created");
+			}
+
+			for (owner, name, note, mappable_address, lat, long, alt) in &self.spatial_things {
+				Pallet::<T>::do_create_spatial_thing(
+					agent(owner),
+					bounded(name),
+					noted(note),
+					noted(mappable_address),
+					*lat,
+					*long,
+					*alt,
+				)
+				.expect("genesis spatial thing can be created");
+			}
+
+			for (owner, name, note) in &self.process_specifications {
+				Pallet::<T>::do_create_process_specification(
+					agent(owner),
+					bounded(name),
+					noted(note),
+				)
+				.expect("genesis process specification can be created");
+			}
+
+			for (owner, name) in &self.concept_schemes {
+				Pallet::<T>::do_create_concept_scheme(agent(owner), bounded(name))
+					.expect("genesis concept scheme can be created");
+			}
+
+			for (scheme, label, broader) in &self.concepts {
+				let owner =
+					ConceptSchemes::<T>::get(scheme).expect("genesis concept scheme exists").owner;
+				Pallet::<T>::do_create_concept(owner, *scheme, bounded(label), *broader)
+					.expect("genesis concept can be created");
+			}
+
+			for (owner, name, note, classified_as, unit_of_resource, unit_of_effort) in
+				&self.resource_specifications
+			{
+				let resource_classified_as = classified_as
+					.clone()
+					.try_into()
+					.expect("genesis classifications fit MaxArrayLength");
+				Pallet::<T>::do_create_resource_specification(
+					agent(owner),
+					bounded(name),
+					Default::default(),
+					noted(note),
+					resource_classified_as,
+					*unit_of_resource,
+					*unit_of_effort,
+				)
+				.expect("genesis resource specification can be created");
+			}
+
+			for process_spec_id in &self.archived_process_specifications {
+				let owner = ProcessSpecifications::<T>::get(process_spec_id)
+					.expect("archived genesis process specification exists")
+					.owner;
+				Pallet::<T>::do_archive_process_specification(&owner, *process_spec_id)
+					.expect("genesis process specification can be archived");
+			}
+
+			for resource_spec_id in &self.archived_resource_specifications {
+				let owner = ResourceSpecifications::<T>::get(resource_spec_id)
+					.expect("archived genesis resource specification exists")
+					.owner;
+				Pallet::<T>::do_archive_resource_specification(&owner, *resource_spec_id)
+					.expect("genesis resource specification can be archived");
+			}
+
+			for hash in &texts {
+				T::Preimages::unrequest(hash);
+			}
+		}
+	}
+
+	/// Convert a genesis string into a bounded one.
+	#[cfg(feature = "std")]
+	fn bounded<S: Get<u32>>(s: &[u8]) -> BoundedVec<u8, S> {
+		s.to_vec().try_into().expect("genesis string fits its bound")
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// Register an agent with success
 		AgentRegistered(T::AccountId),
+		/// A unit was created. [unit_id, owner]
+		UnitCreated(UnitId, T::AccountId),
+		/// A spatial thing was created. [spatial_thing_id, owner]
+		SpatialThingCreated(SpatialThingId, T::AccountId),
+		/// A process specification was created. [process_spec_id, owner]
+		ProcessSpecificationCreated(ProcessSpecificationId, T::AccountId),
+		/// A resource specification was created. [resource_spec_id, owner]
+		ResourceSpecificationCreated(ResourceSpecificationId, T::AccountId),
+		/// A concept scheme was created. [scheme_id, owner]
+		ConceptSchemeCreated(ConceptSchemeId, T::AccountId),
+		/// A concept was created. [concept_id, scheme_id]
+		ConceptCreated(ConceptId, ConceptSchemeId),
+		/// A concept was deleted. [concept_id]
+		ConceptDeleted(ConceptId),
+		/// Records were created by a batch, in the order of its items. [records, owner]
+		BatchCreated(Vec<CreatedRecord>, T::AccountId),
+		/// A process specification was updated. [process_spec_id, revision]
+		ProcessSpecificationUpdated(ProcessSpecificationId, u32),
+		/// A process specification was archived. [process_spec_id]
+		ProcessSpecificationArchived(ProcessSpecificationId),
+		/// A resource specification was updated. [resource_spec_id, revision]
+		ResourceSpecificationUpdated(ResourceSpecificationId, u32),
+		/// A resource specification was archived. [resource_spec_id]
+		ResourceSpecificationArchived(ResourceSpecificationId),
+		/// An agent was deregistered. [who]
+		AgentDeregistered(T::AccountId),
+		/// An agent initiated a key rotation. [old, new]
+		KeyRotationInitiated(T::AccountId, T::AccountId),
+		/// An agent identity and its records moved to a new account. [old, new]
+		AgentKeyRotated(T::AccountId, T::AccountId),
+		/// The registration mode was changed. [mode]
+		RegistrationModeSet(RegistrationMode),
+		/// An account applied for registration as an agent. [who]
+		RegistrationApplied(T::AccountId),
+		/// An agent vouched for an applicant. [voucher, applicant]
+		Vouched(T::AccountId, T::AccountId),
+		/// An application was approved. [who]
+		ApplicationApproved(T::AccountId),
+		/// An application was rejected and its deposit slashed. [who]
+		ApplicationRejected(T::AccountId),
+		/// An application was withdrawn by the applicant. [who]
+		ApplicationWithdrawn(T::AccountId),
+		/// A unit was updated. [unit_id]
+		UnitUpdated(UnitId),
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		AgentAlreadyRegistered,
 		AgentIsNotRegistered,
+		/// The unit does not exist.
+		UnitNotFound,
+		/// The spatial thing does not exist.
+		SpatialThingNotFound,
+		/// The process specification does not exist.
+		ProcessSpecificationNotFound,
+		/// The resource specification does not exist.
+		ResourceSpecificationNotFound,
+		/// Only the agent who created a record may change or delete it.
+		NotOwner,
+		/// No identifiers are left for this record type.
+		IdOverflow,
+		/// An image CID is not a valid binary CID, or uses an unsupported hash function.
+		InvalidCid,
+		/// An image is larger than the content store accepts.
+		ImageTooLarge,
+		/// The preimage of a text hash has not been noted.
+		TextNotNoted,
+		/// The concept scheme does not exist.
+		ConceptSchemeNotFound,
+		/// The concept does not exist.
+		ConceptNotFound,
+		/// A broader concept must belong to the same scheme as its narrower concepts.
+		ConceptSchemeMismatch,
+		/// The concept still has narrower concepts or classifies resource specifications.
+		ConceptInUse,
+		/// The unit is still the default unit of a resource specification.
+		UnitInUse,
+		/// A batch item references an item that is not earlier in the batch or creates another
+		/// type of record.
+		InvalidBatchReference,
+		/// The specification is archived and can no longer be changed.
+		Archived,
+		/// The agent owns more records than the given record count.
+		InvalidRecordCount,
+		/// The agent owns the maximum number of records.
+		TooManyRecords,
+		/// The account has not initiated a key rotation to the caller.
+		NoKeyRotation,
+		/// A vouched registration mode must require between one and `MaxVouchers` vouchers.
+		InvalidRegistrationMode,
+		/// The account already applied for registration.
+		AlreadyApplied,
+		/// The account has not applied for registration.
+		ApplicationNotFound,
+		/// Applications are not vouched for in the current registration mode.
+		VouchingDisabled,
+		/// The agent already vouched for the applicant.
+		AlreadyVouched,
+		/// The application has the maximum number of vouchers.
+		TooManyVouchers,
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// TODO Get agent public key, should be RPC instead or from client side
 		#[pallet::call_index(0)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::get_my_agent_pubkey())]
 		pub fn get_my_agent_pubkey(origin: OriginFor<T>) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 
 			Ok(())
 		}
 
-		/// Register agent
+		/// Register agent. Unless registration is open, this applies for registration instead and
+		/// reserves `DepositBase` until the application is decided
 		/// TODO instead of put it in a vector, should better use a map
 		#[pallet::call_index(1)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::register_agent())]
 		pub fn register_agent(origin: OriginFor<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(!Agents::<T>::contains_key(&who), Error::<T>::AgentAlreadyRegistered);
 
-			Agents::<T>::insert(&who, true);
+			if AgentRegistrationMode::<T>::get() == RegistrationMode::Open {
+				Self::do_register_agent(who);
+				return Ok(())
+			}
 
-			Self::deposit_event(Event::AgentRegistered(who));
+			ensure!(!Applications::<T>::contains_key(&who), Error::<T>::AlreadyApplied);
+
+			let deposit = T::DepositBase::get();
+			T::Currency::reserve(&who, deposit)?;
+			let application = Application::<T> { deposit, vouchers: Default::default() };
+			Applications::<T>::insert(&who, application);
+
+			Self::deposit_event(Event::RegistrationApplied(who));
 
 			Ok(())
 		}
 
 		/// Create an unit
 		#[pallet::call_index(2)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::create_unit(Self::longest(&[label.len(), symbol.len()])))]
 		pub fn create_unit(
 			origin: OriginFor<T>,
-			label: BoundedVec<u8, T::MaxStringLength>,
-			symbol: BoundedVec<u8, T::MaxStringLength>,
+			label: BoundedVec<u8, T::MaxNameLength>,
+			symbol: BoundedVec<u8, T::MaxSymbolLength>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			let unit_id = UnitId::<T>::get();
-			let unit = Unit::<T> {
-				label,
-				symbol,
-			};
-
-			Units::<T>::insert(unit_id, unit);
-			UnitId::<T>::put(unit_id + 1);
+			Self::do_create_unit(who, label, symbol)?;
 
 			Ok(())
 		}
 
 		/// Update an unit
 		#[pallet::call_index(3)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::update_unit(Self::longest(&[label.len(), symbol.len()])))]
 		pub fn update_unit(
 			origin: OriginFor<T>,
-			unit_id: u32,
-			label: BoundedVec<u8, T::MaxStringLength>,
-			symbol: BoundedVec<u8, T::MaxStringLength>,
+			unit_id: UnitId,
+			label: BoundedVec<u8, T::MaxNameLength>,
+			symbol: BoundedVec<u8, T::MaxSymbolLength>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			let unit = Unit::<T> {
+			let old = Units::<T>::get(unit_id).ok_or(Error::<T>::UnitNotFound)?;
+			ensure!(old.owner == who, Error::<T>::NotOwner);
+
+			let mut unit = Unit::<T> {
+				owner: who.clone(),
+				deposit: old.deposit,
 				label,
 				symbol,
 			};
+			unit.deposit = Self::deposit_for(unit.encoded_size());
+			Self::adjust_deposit(&who, old.deposit, unit.deposit)?;
 
 			Units::<T>::insert(unit_id, unit);
 
+			Self::deposit_event(Event::UnitUpdated(unit_id));
+
 			Ok(())
 		}
 
-		/// Delete an unit
+		/// Delete a unit that no resource specification refers to
 		#[pallet::call_index(4)]
-		#[pallet::weight(10_000)]
-		pub fn delete_unit(origin: OriginFor<T>, unit_id: u32) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::delete_unit())]
+		pub fn delete_unit(origin: OriginFor<T>, unit_id: UnitId) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			Units::<T>::remove(unit_id);
-
-			Ok(())
+			Self::do_delete_unit(&who, unit_id)
 		}
 
 		/// Create a spatial thing
 		#[pallet::call_index(5)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::create_spatial_thing(name.len() as u32))]
 		pub fn create_spatial_thing(
 			origin: OriginFor<T>,
-			name: BoundedVec<u8, T::MaxStringLength>,
-			note: Option<BoundedVec<u8, T::MaxStringLength>>,
-			mappable_address: Option<BoundedVec<u8, T::MaxStringLength>>,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			note: Option<H256>,
+			mappable_address: Option<H256>,
 			lat: Option<FixedI64>,
 			long: Option<FixedI64>,
 			alt: Option<FixedI64>,
@@ -237,31 +897,20 @@ pub mod pallet {
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			let spatial_thing_id = SpatialThingId::<T>::get();
-			let spatial_thing = SpatialThing::<T> {
-				name,
-				note,
-				mappable_address,
-				lat,
-				long,
-				alt,
-			};
-
-			SpatialThings::<T>::insert(spatial_thing_id, spatial_thing);
-			SpatialThingId::<T>::put(spatial_thing_id + 1);
+			Self::do_create_spatial_thing(who, name, note, mappable_address, lat, long, alt)?;
 
 			Ok(())
 		}
 
 		/// Update a spatial thing
 		#[pallet::call_index(6)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::update_spatial_thing(name.len() as u32))]
 		pub fn update_spatial_thing(
 			origin: OriginFor<T>,
-			spatial_thing_id: u32,
-			name: BoundedVec<u8, T::MaxStringLength>,
-			note: Option<BoundedVec<u8, T::MaxStringLength>>,
-			mappable_address: Option<BoundedVec<u8, T::MaxStringLength>>,
+			spatial_thing_id: SpatialThingId,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			note: Option<H256>,
+			mappable_address: Option<H256>,
 			lat: Option<FixedI64>,
 			long: Option<FixedI64>,
 			alt: Option<FixedI64>,
@@ -270,7 +919,14 @@ pub mod pallet {
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			let spatial_thing = SpatialThing::<T> {
+			let old = SpatialThings::<T>::get(spatial_thing_id)
+				.ok_or(Error::<T>::SpatialThingNotFound)?;
+			ensure!(old.owner == who, Error::<T>::NotOwner);
+			let texts_len = Self::request_texts(&[note, mappable_address])?;
+
+			let mut spatial_thing = SpatialThing::<T> {
+				owner: who.clone(),
+				deposit: old.deposit,
 				name,
 				note,
 				mappable_address,
@@ -278,169 +934,1160 @@ pub mod pallet {
 				long,
 				alt,
 			};
+			spatial_thing.deposit = Self::deposit_for(spatial_thing.encoded_size() + texts_len);
+			Self::adjust_deposit(&who, old.deposit, spatial_thing.deposit)?;
 
 			SpatialThings::<T>::insert(spatial_thing_id, spatial_thing);
+			Self::release_texts(&[old.note, old.mappable_address]);
 
 			Ok(())
 		}
 
 		/// Delete a spatial thing
 		#[pallet::call_index(7)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::delete_spatial_thing())]
 		pub fn delete_spatial_thing(
 			origin: OriginFor<T>,
-			spatial_thing_id: u32,
+			spatial_thing_id: SpatialThingId,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			SpatialThings::<T>::remove(spatial_thing_id);
-
-			Ok(())
+			Self::do_delete_spatial_thing(&who, spatial_thing_id)
 		}
 
 		/// Create a process specification
 		#[pallet::call_index(8)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::create_process_specification(name.len() as u32))]
 		pub fn create_process_specification(
 			origin: OriginFor<T>,
-			name: BoundedVec<u8, T::MaxStringLength>,
-			note: Option<BoundedVec<u8, T::MaxStringLength>>,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			note: Option<H256>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			let process_spec_id = ProcessSpecificationId::<T>::get();
-			let process_spec = ProcessSpecification::<T> {
-				name,
-				note,
-			};
-
-			ProcessSpecifications::<T>::insert(process_spec_id, process_spec);
-			ProcessSpecificationId::<T>::put(process_spec_id + 1);
+			Self::do_create_process_specification(who, name, note)?;
 
 			Ok(())
 		}
 
 		/// Update a process specification
 		#[pallet::call_index(9)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::update_process_specification(name.len() as u32))]
 		pub fn update_process_specification(
 			origin: OriginFor<T>,
-			process_spec_id: u32,
-			name: BoundedVec<u8, T::MaxStringLength>,
-			note: Option<BoundedVec<u8, T::MaxStringLength>>,
+			process_spec_id: ProcessSpecificationId,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			note: Option<H256>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			let process_spec = ProcessSpecification::<T> {
+			let old = ProcessSpecifications::<T>::get(process_spec_id)
+				.ok_or(Error::<T>::ProcessSpecificationNotFound)?;
+			ensure!(old.owner == who, Error::<T>::NotOwner);
+			ensure!(old.archived_at.is_none(), Error::<T>::Archived);
+			let texts_len = Self::request_texts(&[note])?;
+
+			let mut process_spec = ProcessSpecification::<T> {
+				owner: who.clone(),
+				deposit: old.deposit,
 				name,
 				note,
+				revision: old.revision.saturating_add(1),
+				previous_version: Some(T::Hashing::hash_of(&old)),
+				archived_at: None,
 			};
+			process_spec.deposit = Self::deposit_for(process_spec.encoded_size() + texts_len);
+			Self::adjust_deposit(&who, old.deposit, process_spec.deposit)?;
 
+			let revision = process_spec.revision;
 			ProcessSpecifications::<T>::insert(process_spec_id, process_spec);
+			Self::release_texts(&[old.note]);
+
+			Self::deposit_event(Event::ProcessSpecificationUpdated(process_spec_id, revision));
 
 			Ok(())
 		}
 
-		/// Delete a process specification
+		/// Archive a process specification. It is kept, along with its deposit and note, so it
+		/// can still be resolved, but it can no longer be updated
 		#[pallet::call_index(10)]
-		#[pallet::weight(10_000)]
-		pub fn delete_process_specification(
+		#[pallet::weight(T::WeightInfo::archive_process_specification())]
+		pub fn archive_process_specification(
 			origin: OriginFor<T>,
-			process_spec_id: u32,
+			process_spec_id: ProcessSpecificationId,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			ProcessSpecifications::<T>::remove(process_spec_id);
-
-			Ok(())
+			Self::do_archive_process_specification(&who, process_spec_id)
 		}
 
 		/// Create a resource specification
 		#[pallet::call_index(11)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::create_resource_specification(
+			name.len() as u32,
+			Self::longest(&[images.len(), resource_classified_as.len()]),
+		))]
 		pub fn create_resource_specification(
 			origin: OriginFor<T>,
-			name: BoundedVec<u8, T::MaxStringLength>,
-			images: BoundedVec<T::Hash, T::MaxArrayLength>,
-			note: Option<BoundedVec<u8, T::MaxStringLength>>,
-			resource_classified_as: BoundedVec<BoundedVec<u8, T::MaxStringLength>, T::MaxArrayLength>,
-			default_unit_of_resource_id: Option<u32>,
-			default_unit_of_effort_id: Option<u32>,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			images: BoundedVec<Image<T>, T::MaxArrayLength>,
+			note: Option<H256>,
+			resource_classified_as: BoundedVec<ConceptId, T::MaxArrayLength>,
+			default_unit_of_resource_id: Option<UnitId>,
+			default_unit_of_effort_id: Option<UnitId>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			let resource_spec_id = ResourceSpecificationId::<T>::get();
-			let resource_spec = ResourceSpecification::<T> {
+			Self::do_create_resource_specification(
+				who,
 				name,
 				images,
 				note,
 				resource_classified_as,
 				default_unit_of_resource_id,
 				default_unit_of_effort_id,
-			};
-
-			ResourceSpecifications::<T>::insert(resource_spec_id, resource_spec);
-			ResourceSpecificationId::<T>::put(resource_spec_id + 1);
+			)?;
 
 			Ok(())
 		}
 
 		/// Update a resource specification
 		#[pallet::call_index(12)]
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::update_resource_specification(
+			name.len() as u32,
+			Self::longest(&[images.len(), resource_classified_as.len()]),
+		))]
 		pub fn update_resource_specification(
 			origin: OriginFor<T>,
-			resource_spec_id: u32,
-			name: BoundedVec<u8, T::MaxStringLength>,
-			images: BoundedVec<T::Hash, T::MaxArrayLength>,
-			note: Option<BoundedVec<u8, T::MaxStringLength>>,
-			resource_classified_as: BoundedVec<BoundedVec<u8, T::MaxStringLength>, T::MaxArrayLength>,
-			default_unit_of_resource_id: Option<u32>,
-			default_unit_of_effort_id: Option<u32>,
+			resource_spec_id: ResourceSpecificationId,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			images: BoundedVec<Image<T>, T::MaxArrayLength>,
+			note: Option<H256>,
+			resource_classified_as: BoundedVec<ConceptId, T::MaxArrayLength>,
+			default_unit_of_resource_id: Option<UnitId>,
+			default_unit_of_effort_id: Option<UnitId>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			let resource_spec = ResourceSpecification::<T> {
+			let old = ResourceSpecifications::<T>::get(resource_spec_id)
+				.ok_or(Error::<T>::ResourceSpecificationNotFound)?;
+			ensure!(old.owner == who, Error::<T>::NotOwner);
+			ensure!(old.archived_at.is_none(), Error::<T>::Archived);
+			Self::ensure_valid_images(&images)?;
+			Self::ensure_concepts_exist(&resource_classified_as)?;
+			let units = [default_unit_of_resource_id, default_unit_of_effort_id];
+			Self::ensure_units_exist(&units)?;
+			let texts_len = Self::request_texts(&[note])?;
+
+			let mut resource_spec = ResourceSpecification::<T> {
+				owner: who.clone(),
+				deposit: old.deposit,
 				name,
 				images,
 				note,
 				resource_classified_as,
 				default_unit_of_resource_id,
 				default_unit_of_effort_id,
+				revision: old.revision.saturating_add(1),
+				previous_version: Some(T::Hashing::hash_of(&old)),
+				archived_at: None,
 			};
-
+			resource_spec.deposit = Self::deposit_for(resource_spec.encoded_size() + texts_len);
+			Self::adjust_deposit(&who, old.deposit, resource_spec.deposit)?;
+
+			for concept_id in &old.resource_classified_as {
+				ClassifiedResourceSpecifications::<T>::remove(concept_id, resource_spec_id);
+			}
+			for concept_id in &resource_spec.resource_classified_as {
+				ClassifiedResourceSpecifications::<T>::insert(concept_id, resource_spec_id, ());
+			}
+			let old_units = [old.default_unit_of_resource_id, old.default_unit_of_effort_id];
+			for unit_id in old_units.iter().flatten() {
+				UnitResourceSpecifications::<T>::remove(unit_id, resource_spec_id);
+			}
+			for unit_id in units.iter().flatten() {
+				UnitResourceSpecifications::<T>::insert(unit_id, resource_spec_id, ());
+			}
+			let revision = resource_spec.revision;
 			ResourceSpecifications::<T>::insert(resource_spec_id, resource_spec);
+			Self::release_texts(&[old.note]);
+
+			Self::deposit_event(Event::ResourceSpecificationUpdated(resource_spec_id, revision));
 
 			Ok(())
 		}
 
-		/// Delete a resource specification
+		/// Archive a resource specification. It is kept, along with its deposit, note and
+		/// classifications, so it can still be resolved, but it can no longer be updated and is
+		/// left out of [`Pallet::resource_specifications_classified_under`]
 		#[pallet::call_index(13)]
-		#[pallet::weight(10_000)]
-		pub fn delete_resource_specification(
+		#[pallet::weight(T::WeightInfo::archive_resource_specification())]
+		pub fn archive_resource_specification(
+			origin: OriginFor<T>,
+			resource_spec_id: ResourceSpecificationId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
+
+			Self::do_archive_resource_specification(&who, resource_spec_id)
+		}
+
+		/// Create a concept scheme
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::create_concept_scheme(name.len() as u32))]
+		pub fn create_concept_scheme(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, T::MaxNameLength>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
+
+			Self::do_create_concept_scheme(who, name)?;
+
+			Ok(())
+		}
+
+		/// Create a concept in a scheme owned by the caller, optionally narrowing a broader
+		/// concept of the same scheme
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::create_concept(label.len() as u32))]
+		pub fn create_concept(
+			origin: OriginFor<T>,
+			scheme_id: ConceptSchemeId,
+			label: BoundedVec<u8, T::MaxNameLength>,
+			broader: Option<ConceptId>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
+
+			Self::do_create_concept(who, scheme_id, label, broader)?;
+
+			Ok(())
+		}
+
+		/// Delete a concept that has no narrower concepts and classifies no resource
+		/// specifications
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::delete_concept())]
+		pub fn delete_concept(origin: OriginFor<T>, concept_id: ConceptId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
+
+			let concept = Concepts::<T>::get(concept_id).ok_or(Error::<T>::ConceptNotFound)?;
+			ensure!(concept.owner == who, Error::<T>::NotOwner);
+			ensure!(
+				NarrowerConcepts::<T>::iter_key_prefix(concept_id).next().is_none() &&
+					ClassifiedResourceSpecifications::<T>::iter_key_prefix(concept_id)
+						.next()
+						.is_none(),
+				Error::<T>::ConceptInUse
+			);
+
+			T::Currency::unreserve(&who, concept.deposit);
+			if let Some(broader) = concept.broader {
+				NarrowerConcepts::<T>::remove(broader, concept_id);
+			}
+			Concepts::<T>::remove(concept_id);
+			Self::disown(&who, CreatedRecord::Concept(concept_id));
+
+			Self::deposit_event(Event::ConceptDeleted(concept_id));
+
+			Ok(())
+		}
+
+		/// Create several records at once. Either all of them are created or, if any item
+		/// fails, none is.
+		#[pallet::call_index(17)]
+		#[pallet::weight(Self::batch_weight(items))]
+		pub fn batch_create(
+			origin: OriginFor<T>,
+			items: BoundedVec<BatchItem<T>, T::MaxBatchLength>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
+
+			let mut created = Vec::with_capacity(items.len());
+			for item in items {
+				let record = Self::do_create_batch_item(who.clone(), item, &created)?;
+				created.push(record);
+			}
+
+			Self::deposit_event(Event::BatchCreated(created, who));
+
+			Ok(())
+		}
+
+		/// Deregister the caller. Its spatial things are deleted and its specifications archived.
+		/// Its units are deleted unless a resource specification refers to them. Those units are
+		/// kept, as are concept schemes and concepts, which other agents may classify records
+		/// with. Kept records still hold their deposits. To hand the records over to another agent
+		/// instead, rotate the key to that agent first. `record_count` is an upper bound of the
+		/// number of records owned by the caller
+		#[pallet::call_index(18)]
+		#[pallet::weight(Self::deregister_weight(*record_count))]
+		pub fn deregister_agent(origin: OriginFor<T>, record_count: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
+
+			for record in Self::owned_records(&who, record_count)? {
+				match record {
+					CreatedRecord::Unit(unit_id) if Self::unit_in_use(unit_id) => {},
+					CreatedRecord::Unit(unit_id) => Self::do_delete_unit(&who, unit_id)?,
+					CreatedRecord::SpatialThing(spatial_thing_id) =>
+						Self::do_delete_spatial_thing(&who, spatial_thing_id)?,
+					CreatedRecord::ProcessSpecification(process_spec_id) => {
+						let archived = ProcessSpecifications::<T>::get(process_spec_id)
+							.and_then(|process_spec| process_spec.archived_at)
+							.is_some();
+						if !archived {
+							Self::do_archive_process_specification(&who, process_spec_id)?;
+						}
+					},
+					CreatedRecord::ResourceSpecification(resource_spec_id) => {
+						let archived = ResourceSpecifications::<T>::get(resource_spec_id)
+							.and_then(|resource_spec| resource_spec.archived_at)
+							.is_some();
+						if !archived {
+							Self::do_archive_resource_specification(&who, resource_spec_id)?;
+						}
+					},
+					CreatedRecord::ConceptScheme(_) | CreatedRecord::Concept(_) => {},
+				}
+			}
+			Agents::<T>::remove(&who);
+			KeyRotations::<T>::remove(&who);
+
+			Self::deposit_event(Event::AgentDeregistered(who));
+
+			Ok(())
+		}
+
+		/// Initiate moving the caller's agent identity and records to the `new` account, which
+		/// has to accept it with [`Pallet::accept_key_rotation`]. Initiating another rotation
+		/// replaces this one
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::initiate_key_rotation())]
+		pub fn initiate_key_rotation(origin: OriginFor<T>, new: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
+
+			KeyRotations::<T>::insert(&who, &new);
+
+			Self::deposit_event(Event::KeyRotationInitiated(who, new));
+
+			Ok(())
+		}
+
+		/// Accept a key rotation initiated by the `old` account. Its records, along with their
+		/// deposits, move to the caller, which becomes a registered agent in place of `old`. The
+		/// caller may already be an agent, which hands the records of `old` over to it.
+		/// `record_count` is an upper bound of the number of records owned by `old`
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::accept_key_rotation(*record_count))]
+		pub fn accept_key_rotation(
+			origin: OriginFor<T>,
+			old: T::AccountId,
+			record_count: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(KeyRotations::<T>::get(&old) == Some(who.clone()), Error::<T>::NoKeyRotation);
+
+			for record in Self::owned_records(&old, record_count)? {
+				match record {
+					CreatedRecord::Unit(id) =>
+						Units::<T>::try_mutate(id, |unit| Self::transfer(unit, &old, &who)),
+					CreatedRecord::SpatialThing(id) =>
+						SpatialThings::<T>::try_mutate(id, |spatial_thing| {
+							Self::transfer(spatial_thing, &old, &who)
+						}),
+					CreatedRecord::ProcessSpecification(id) =>
+						ProcessSpecifications::<T>::try_mutate(id, |process_spec| {
+							Self::transfer(process_spec, &old, &who)
+						}),
+					CreatedRecord::ResourceSpecification(id) =>
+						ResourceSpecifications::<T>::try_mutate(id, |resource_spec| {
+							Self::transfer(resource_spec, &old, &who)
+						}),
+					CreatedRecord::ConceptScheme(id) =>
+						ConceptSchemes::<T>::try_mutate(id, |scheme| {
+							Self::transfer(scheme, &old, &who)
+						}),
+					CreatedRecord::Concept(id) =>
+						Concepts::<T>::try_mutate(id, |concept| Self::transfer(concept, &old, &who)),
+				}?;
+				Self::disown(&old, record);
+				Self::own(&who, record)?;
+			}
+			KeyRotations::<T>::remove(&old);
+			Agents::<T>::remove(&old);
+			Agents::<T>::insert(&who, true);
+
+			Self::deposit_event(Event::AgentKeyRotated(old, who));
+
+			Ok(())
+		}
+
+		/// Set how accounts become agents. Pending applications are kept
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::set_registration_mode())]
+		pub fn set_registration_mode(
+			origin: OriginFor<T>,
+			mode: RegistrationMode,
+		) -> DispatchResult {
+			T::RegistrationOrigin::ensure_origin(origin)?;
+
+			ensure!(Self::valid_registration_mode(mode), Error::<T>::InvalidRegistrationMode);
+
+			AgentRegistrationMode::<T>::put(mode);
+
+			Self::deposit_event(Event::RegistrationModeSet(mode));
+
+			Ok(())
+		}
+
+		/// Approve an application and register the applicant
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::approve_application())]
+		pub fn approve_application(
 			origin: OriginFor<T>,
-			resource_spec_id: u32,
+			applicant: T::AccountId,
 		) -> DispatchResult {
+			T::RegistrationOrigin::ensure_origin(origin)?;
+
+			ensure!(Applications::<T>::contains_key(&applicant), Error::<T>::ApplicationNotFound);
+
+			Self::deposit_event(Event::ApplicationApproved(applicant.clone()));
+			Self::do_register_agent(applicant);
+
+			Ok(())
+		}
+
+		/// Reject an application and slash its deposit
+		#[pallet::call_index(23)]
+		#[pallet::weight(T::WeightInfo::reject_application())]
+		pub fn reject_application(origin: OriginFor<T>, applicant: T::AccountId) -> DispatchResult {
+			T::RegistrationOrigin::ensure_origin(origin)?;
+
+			let application =
+				Applications::<T>::take(&applicant).ok_or(Error::<T>::ApplicationNotFound)?;
+			let _ = T::Currency::slash_reserved(&applicant, application.deposit);
+
+			Self::deposit_event(Event::ApplicationRejected(applicant));
+
+			Ok(())
+		}
+
+		/// Withdraw the application of the caller and refund its deposit
+		#[pallet::call_index(24)]
+		#[pallet::weight(T::WeightInfo::withdraw_application())]
+		pub fn withdraw_application(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let application =
+				Applications::<T>::take(&who).ok_or(Error::<T>::ApplicationNotFound)?;
+			T::Currency::unreserve(&who, application.deposit);
+
+			Self::deposit_event(Event::ApplicationWithdrawn(who));
+
+			Ok(())
+		}
+
+		/// Vouch for an applicant. The applicant is registered once enough agents vouched for it
+		#[pallet::call_index(25)]
+		#[pallet::weight(T::WeightInfo::vouch())]
+		pub fn vouch(origin: OriginFor<T>, applicant: T::AccountId) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			ensure!(Agents::<T>::contains_key(&who), Error::<T>::AgentIsNotRegistered);
 
-			ResourceSpecifications::<T>::remove(resource_spec_id);
+			let required = match AgentRegistrationMode::<T>::get() {
+				RegistrationMode::Vouched(required) => required,
+				_ => return Err(Error::<T>::VouchingDisabled.into()),
+			};
+
+			let vouchers = Applications::<T>::try_mutate(
+				&applicant,
+				|application| -> Result<u32, Error<T>> {
+					let application =
+						application.as_mut().ok_or(Error::<T>::ApplicationNotFound)?;
+					ensure!(!application.vouchers.contains(&who), Error::<T>::AlreadyVouched);
+					application
+						.vouchers
+						.try_push(who.clone())
+						.map_err(|_| Error::<T>::TooManyVouchers)?;
+					Ok(application.vouchers.len() as u32)
+				},
+			)?;
+
+			Self::deposit_event(Event::Vouched(who, applicant.clone()));
+
+			if vouchers >= required {
+				Self::do_register_agent(applicant);
+			}
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Register `who` as an agent and refund the deposit of its pending application.
+		fn do_register_agent(who: T::AccountId) {
+			if let Some(application) = Applications::<T>::take(&who) {
+				T::Currency::unreserve(&who, application.deposit);
+			}
+			Agents::<T>::insert(&who, true);
+
+			Self::deposit_event(Event::AgentRegistered(who));
+		}
+
+		/// Whether `mode` can be set, i.e. a vouched mode can be satisfied.
+		pub(crate) fn valid_registration_mode(mode: RegistrationMode) -> bool {
+			match mode {
+				RegistrationMode::Vouched(required) =>
+					(1..=T::MaxVouchers::get()).contains(&required),
+				_ => true,
+			}
+		}
+
+		/// Store a unit owned by `who` and reserve its deposit.
+		pub(crate) fn do_create_unit(
+			who: T::AccountId,
+			label: BoundedVec<u8, T::MaxNameLength>,
+			symbol: BoundedVec<u8, T::MaxSymbolLength>,
+		) -> Result<UnitId, DispatchError> {
+			let unit_id = NextUnitId::<T>::get();
+			let next_id = unit_id.next().ok_or(Error::<T>::IdOverflow)?;
+			let mut unit = Unit::<T> {
+				owner: who.clone(),
+				deposit: Zero::zero(),
+				label,
+				symbol,
+			};
+			unit.deposit = Self::deposit_for(unit.encoded_size());
+			T::Currency::reserve(&who, unit.deposit)?;
+
+			Units::<T>::insert(unit_id, unit);
+			NextUnitId::<T>::put(next_id);
+			Self::own(&who, CreatedRecord::Unit(unit_id))?;
+
+			Self::deposit_event(Event::UnitCreated(unit_id, who));
+
+			Ok(unit_id)
+		}
+
+		/// Store a spatial thing owned by `who` and reserve its deposit.
+		pub(crate) fn do_create_spatial_thing(
+			who: T::AccountId,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			note: Option<H256>,
+			mappable_address: Option<H256>,
+			lat: Option<FixedI64>,
+			long: Option<FixedI64>,
+			alt: Option<FixedI64>,
+		) -> Result<SpatialThingId, DispatchError> {
+			let texts_len = Self::request_texts(&[note, mappable_address])?;
+
+			let spatial_thing_id = NextSpatialThingId::<T>::get();
+			let next_id = spatial_thing_id.next().ok_or(Error::<T>::IdOverflow)?;
+			let mut spatial_thing = SpatialThing::<T> {
+				owner: who.clone(),
+				deposit: Zero::zero(),
+				name,
+				note,
+				mappable_address,
+				lat,
+				long,
+				alt,
+			};
+			spatial_thing.deposit = Self::deposit_for(spatial_thing.encoded_size() + texts_len);
+			T::Currency::reserve(&who, spatial_thing.deposit)?;
+
+			SpatialThings::<T>::insert(spatial_thing_id, spatial_thing);
+			NextSpatialThingId::<T>::put(next_id);
+			Self::own(&who, CreatedRecord::SpatialThing(spatial_thing_id))?;
+
+			Self::deposit_event(Event::SpatialThingCreated(spatial_thing_id, who));
+
+			Ok(spatial_thing_id)
+		}
+
+		/// Store a process specification owned by `who` and reserve its deposit.
+		pub(crate) fn do_create_process_specification(
+			who: T::AccountId,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			note: Option<H256>,
+		) -> Result<ProcessSpecificationId, DispatchError> {
+			let texts_len = Self::request_texts(&[note])?;
+
+			let process_spec_id = NextProcessSpecificationId::<T>::get();
+			let next_id = process_spec_id.next().ok_or(Error::<T>::IdOverflow)?;
+			let mut process_spec = ProcessSpecification::<T> {
+				owner: who.clone(),
+				deposit: Zero::zero(),
+				name,
+				note,
+				revision: 0,
+				previous_version: None,
+				archived_at: None,
+			};
+			process_spec.deposit = Self::deposit_for(process_spec.encoded_size() + texts_len);
+			T::Currency::reserve(&who, process_spec.deposit)?;
+
+			ProcessSpecifications::<T>::insert(process_spec_id, process_spec);
+			NextProcessSpecificationId::<T>::put(next_id);
+			Self::own(&who, CreatedRecord::ProcessSpecification(process_spec_id))?;
+
+			Self::deposit_event(Event::ProcessSpecificationCreated(process_spec_id, who));
+
+			Ok(process_spec_id)
+		}
+
+		/// Store a resource specification owned by `who` and reserve its deposit.
+		pub(crate) fn do_create_resource_specification(
+			who: T::AccountId,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			images: BoundedVec<Image<T>, T::MaxArrayLength>,
+			note: Option<H256>,
+			resource_classified_as: BoundedVec<ConceptId, T::MaxArrayLength>,
+			default_unit_of_resource_id: Option<UnitId>,
+			default_unit_of_effort_id: Option<UnitId>,
+		) -> Result<ResourceSpecificationId, DispatchError> {
+			Self::ensure_valid_images(&images)?;
+			Self::ensure_concepts_exist(&resource_classified_as)?;
+			let units = [default_unit_of_resource_id, default_unit_of_effort_id];
+			Self::ensure_units_exist(&units)?;
+			let texts_len = Self::request_texts(&[note])?;
+
+			let resource_spec_id = NextResourceSpecificationId::<T>::get();
+			let next_id = resource_spec_id.next().ok_or(Error::<T>::IdOverflow)?;
+			let mut resource_spec = ResourceSpecification::<T> {
+				owner: who.clone(),
+				deposit: Zero::zero(),
+				name,
+				images,
+				note,
+				resource_classified_as,
+				default_unit_of_resource_id,
+				default_unit_of_effort_id,
+				revision: 0,
+				previous_version: None,
+				archived_at: None,
+			};
+			resource_spec.deposit = Self::deposit_for(resource_spec.encoded_size() + texts_len);
+			T::Currency::reserve(&who, resource_spec.deposit)?;
+
+			for concept_id in &resource_spec.resource_classified_as {
+				ClassifiedResourceSpecifications::<T>::insert(concept_id, resource_spec_id, ());
+			}
+			for unit_id in units.iter().flatten() {
+				UnitResourceSpecifications::<T>::insert(unit_id, resource_spec_id, ());
+			}
+			ResourceSpecifications::<T>::insert(resource_spec_id, resource_spec);
+			NextResourceSpecificationId::<T>::put(next_id);
+			Self::own(&who, CreatedRecord::ResourceSpecification(resource_spec_id))?;
+
+			Self::deposit_event(Event::ResourceSpecificationCreated(resource_spec_id, who));
+
+			Ok(resource_spec_id)
+		}
+
+		/// Store a concept scheme owned by `who` and reserve its deposit.
+		pub(crate) fn do_create_concept_scheme(
+			who: T::AccountId,
+			name: BoundedVec<u8, T::MaxNameLength>,
+		) -> Result<ConceptSchemeId, DispatchError> {
+			let scheme_id = NextConceptSchemeId::<T>::get();
+			let next_id = scheme_id.next().ok_or(Error::<T>::IdOverflow)?;
+			let mut scheme = ConceptScheme::<T> { owner: who.clone(), deposit: Zero::zero(), name };
+			scheme.deposit = Self::deposit_for(scheme.encoded_size());
+			T::Currency::reserve(&who, scheme.deposit)?;
+
+			ConceptSchemes::<T>::insert(scheme_id, scheme);
+			NextConceptSchemeId::<T>::put(next_id);
+			Self::own(&who, CreatedRecord::ConceptScheme(scheme_id))?;
+
+			Self::deposit_event(Event::ConceptSchemeCreated(scheme_id, who));
+
+			Ok(scheme_id)
+		}
+
+		/// Store a concept of a scheme owned by `who` and reserve its deposit.
+		pub(crate) fn do_create_concept(
+			who: T::AccountId,
+			scheme_id: ConceptSchemeId,
+			label: BoundedVec<u8, T::MaxNameLength>,
+			broader: Option<ConceptId>,
+		) -> Result<ConceptId, DispatchError> {
+			let scheme =
+				ConceptSchemes::<T>::get(scheme_id).ok_or(Error::<T>::ConceptSchemeNotFound)?;
+			ensure!(scheme.owner == who, Error::<T>::NotOwner);
+			if let Some(broader) = broader {
+				let broader = Concepts::<T>::get(broader).ok_or(Error::<T>::ConceptNotFound)?;
+				ensure!(broader.scheme == scheme_id, Error::<T>::ConceptSchemeMismatch);
+			}
+
+			let concept_id = NextConceptId::<T>::get();
+			let next_id = concept_id.next().ok_or(Error::<T>::IdOverflow)?;
+			let mut concept = Concept::<T> {
+				owner: who.clone(),
+				deposit: Zero::zero(),
+				scheme: scheme_id,
+				label,
+				broader,
+			};
+			concept.deposit = Self::deposit_for(concept.encoded_size());
+			T::Currency::reserve(&who, concept.deposit)?;
+
+			Concepts::<T>::insert(concept_id, concept);
+			if let Some(broader) = broader {
+				NarrowerConcepts::<T>::insert(broader, concept_id, ());
+			}
+			NextConceptId::<T>::put(next_id);
+			Self::own(&who, CreatedRecord::Concept(concept_id))?;
+
+			Self::deposit_event(Event::ConceptCreated(concept_id, scheme_id));
+
+			Ok(concept_id)
+		}
+
+		/// Delete a unit owned by `who` and release its deposit.
+		fn do_delete_unit(who: &T::AccountId, unit_id: UnitId) -> DispatchResult {
+			let unit = Units::<T>::get(unit_id).ok_or(Error::<T>::UnitNotFound)?;
+			ensure!(&unit.owner == who, Error::<T>::NotOwner);
+			ensure!(!Self::unit_in_use(unit_id), Error::<T>::UnitInUse);
+
+			T::Currency::unreserve(who, unit.deposit);
+			Units::<T>::remove(unit_id);
+			Self::disown(who, CreatedRecord::Unit(unit_id));
+
+			Ok(())
+		}
+
+		/// Delete a spatial thing owned by `who` and release its deposit and texts.
+		fn do_delete_spatial_thing(
+			who: &T::AccountId,
+			spatial_thing_id: SpatialThingId,
+		) -> DispatchResult {
+			let spatial_thing = SpatialThings::<T>::get(spatial_thing_id)
+				.ok_or(Error::<T>::SpatialThingNotFound)?;
+			ensure!(&spatial_thing.owner == who, Error::<T>::NotOwner);
+
+			T::Currency::unreserve(who, spatial_thing.deposit);
+			SpatialThings::<T>::remove(spatial_thing_id);
+			Self::disown(who, CreatedRecord::SpatialThing(spatial_thing_id));
+			Self::release_texts(&[spatial_thing.note, spatial_thing.mappable_address]);
+
+			Ok(())
+		}
+
+		/// Archive a process specification owned by `who` as a new revision.
+		fn do_archive_process_specification(
+			who: &T::AccountId,
+			process_spec_id: ProcessSpecificationId,
+		) -> DispatchResult {
+			let old = ProcessSpecifications::<T>::get(process_spec_id)
+				.ok_or(Error::<T>::ProcessSpecificationNotFound)?;
+			ensure!(&old.owner == who, Error::<T>::NotOwner);
+			ensure!(old.archived_at.is_none(), Error::<T>::Archived);
+
+			let mut process_spec = ProcessSpecification::<T> {
+				revision: old.revision.saturating_add(1),
+				previous_version: Some(T::Hashing::hash_of(&old)),
+				archived_at: Some(frame_system::Pallet::<T>::block_number()),
+				..old.clone()
+			};
+			let texts_len = Self::texts_len(&[old.note]);
+			process_spec.deposit = Self::deposit_for(process_spec.encoded_size() + texts_len);
+			Self::adjust_deposit(who, old.deposit, process_spec.deposit)?;
+
+			ProcessSpecifications::<T>::insert(process_spec_id, process_spec);
+
+			Self::deposit_event(Event::ProcessSpecificationArchived(process_spec_id));
+
+			Ok(())
+		}
+
+		/// Archive a resource specification owned by `who` as a new revision.
+		fn do_archive_resource_specification(
+			who: &T::AccountId,
+			resource_spec_id: ResourceSpecificationId,
+		) -> DispatchResult {
+			let old = ResourceSpecifications::<T>::get(resource_spec_id)
+				.ok_or(Error::<T>::ResourceSpecificationNotFound)?;
+			ensure!(&old.owner == who, Error::<T>::NotOwner);
+			ensure!(old.archived_at.is_none(), Error::<T>::Archived);
+
+			let mut resource_spec = ResourceSpecification::<T> {
+				revision: old.revision.saturating_add(1),
+				previous_version: Some(T::Hashing::hash_of(&old)),
+				archived_at: Some(frame_system::Pallet::<T>::block_number()),
+				..old.clone()
+			};
+			let texts_len = Self::texts_len(&[old.note]);
+			resource_spec.deposit = Self::deposit_for(resource_spec.encoded_size() + texts_len);
+			Self::adjust_deposit(who, old.deposit, resource_spec.deposit)?;
+
+			ResourceSpecifications::<T>::insert(resource_spec_id, resource_spec);
+
+			Self::deposit_event(Event::ResourceSpecificationArchived(resource_spec_id));
 
 			Ok(())
 		}
 
+		/// Add `record` to the records owned by `who`, up to `MaxOwnedRecords` of them.
+		fn own(who: &T::AccountId, record: CreatedRecord) -> DispatchResult {
+			OwnedRecordCount::<T>::try_mutate(who, |count| {
+				ensure!(*count < T::MaxOwnedRecords::get(), Error::<T>::TooManyRecords);
+				*count += 1;
+				Ok::<_, Error<T>>(())
+			})?;
+			OwnedRecords::<T>::insert(who, record, ());
+
+			Ok(())
+		}
+
+		/// Remove `record` from the records owned by `who`.
+		fn disown(who: &T::AccountId, record: CreatedRecord) {
+			OwnedRecords::<T>::remove(who, record);
+			OwnedRecordCount::<T>::mutate_exists(who, |count| {
+				*count = count.and_then(|count| count.checked_sub(1)).filter(|count| *count > 0)
+			});
+		}
+
+		/// The records owned by `who`, provided there are at most `record_count` of them.
+		fn owned_records(
+			who: &T::AccountId,
+			record_count: u32,
+		) -> Result<Vec<CreatedRecord>, DispatchError> {
+			let records: Vec<_> = OwnedRecords::<T>::iter_key_prefix(who)
+				.take(record_count as usize + 1)
+				.collect();
+			ensure!(records.len() <= record_count as usize, Error::<T>::InvalidRecordCount);
+
+			Ok(records)
+		}
+
+		/// Move a record and its deposit from `old` to `new`.
+		fn transfer<R: Owned<T>>(
+			record: &mut Option<R>,
+			old: &T::AccountId,
+			new: &T::AccountId,
+		) -> DispatchResult {
+			if let Some(record) = record {
+				T::Currency::unreserve(old, record.deposit());
+				T::Currency::reserve(new, record.deposit())?;
+				*record.owner_mut() = new.clone();
+			}
+
+			Ok(())
+		}
+
+		/// Create the record of a batch item owned by `who`, resolving its references to the
+		/// records `created` by the earlier items.
+		fn do_create_batch_item(
+			who: T::AccountId,
+			item: BatchItem<T>,
+			created: &[CreatedRecord],
+		) -> Result<CreatedRecord, DispatchError> {
+			let unit = |reference: BatchRef<UnitId>| {
+				Self::resolve(reference, created, |record| match record {
+					CreatedRecord::Unit(unit_id) => Some(unit_id),
+					_ => None,
+				})
+			};
+			let concept = |reference: BatchRef<ConceptId>| {
+				Self::resolve(reference, created, |record| match record {
+					CreatedRecord::Concept(concept_id) => Some(concept_id),
+					_ => None,
+				})
+			};
+
+			let record = match item {
+				BatchItem::Unit { label, symbol } =>
+					CreatedRecord::Unit(Self::do_create_unit(who, label, symbol)?),
+				BatchItem::SpatialThing { name, note, mappable_address, lat, long, alt } =>
+					CreatedRecord::SpatialThing(Self::do_create_spatial_thing(
+						who,
+						name,
+						note,
+						mappable_address,
+						lat,
+						long,
+						alt,
+					)?),
+				BatchItem::ProcessSpecification { name, note } =>
+					CreatedRecord::ProcessSpecification(Self::do_create_process_specification(
+						who, name, note,
+					)?),
+				BatchItem::ResourceSpecification {
+					name,
+					images,
+					note,
+					resource_classified_as,
+					default_unit_of_resource_id,
+					default_unit_of_effort_id,
+				} => {
+					let resource_classified_as = resource_classified_as
+						.into_iter()
+						.map(concept)
+						.collect::<Result<Vec<_>, _>>()?;
+					CreatedRecord::ResourceSpecification(Self::do_create_resource_specification(
+						who,
+						name,
+						images,
+						note,
+						BoundedVec::truncate_from(resource_classified_as),
+						default_unit_of_resource_id.map(unit).transpose()?,
+						default_unit_of_effort_id.map(unit).transpose()?,
+					)?)
+				},
+				BatchItem::ConceptScheme { name } =>
+					CreatedRecord::ConceptScheme(Self::do_create_concept_scheme(who, name)?),
+				BatchItem::Concept { scheme, label, broader } => {
+					let scheme = Self::resolve(scheme, created, |record| match record {
+						CreatedRecord::ConceptScheme(scheme_id) => Some(scheme_id),
+						_ => None,
+					})?;
+					let broader = broader.map(concept).transpose()?;
+					CreatedRecord::Concept(Self::do_create_concept(who, scheme, label, broader)?)
+				},
+			};
+
+			Ok(record)
+		}
+
+		/// The id of a referenced record, looking up references to batch items in `created`.
+		/// References to existing records are checked by the function creating the record
+		/// referring to them.
+		fn resolve<I>(
+			reference: BatchRef<I>,
+			created: &[CreatedRecord],
+			id: impl Fn(CreatedRecord) -> Option<I>,
+		) -> Result<I, Error<T>> {
+			match reference {
+				BatchRef::Existing(id) => Ok(id),
+				BatchRef::Batch(index) => created
+					.get(index as usize)
+					.and_then(|record| id(*record))
+					.ok_or(Error::<T>::InvalidBatchReference),
+			}
+		}
+
+		/// The weight of deregistering an agent owning `record_count` records, all of the kind
+		/// that is the heaviest to clean up.
+		fn deregister_weight(record_count: u32) -> Weight {
+			T::WeightInfo::deregister_agent_spatial_things(record_count)
+				.max(T::WeightInfo::deregister_agent_resource_specifications(record_count))
+		}
+
+		/// The weight of a batch, the sum of the weights of creating each of its records.
+		fn batch_weight(items: &[BatchItem<T>]) -> Weight {
+			items.iter().fold(Weight::zero(), |weight, item| {
+				weight.saturating_add(match item {
+					BatchItem::Unit { label, symbol } =>
+						T::WeightInfo::create_unit(Self::longest(&[label.len(), symbol.len()])),
+					BatchItem::SpatialThing { name, .. } =>
+						T::WeightInfo::create_spatial_thing(name.len() as u32),
+					BatchItem::ProcessSpecification { name, .. } =>
+						T::WeightInfo::create_process_specification(name.len() as u32),
+					BatchItem::ResourceSpecification {
+						name,
+						images,
+						resource_classified_as,
+						..
+					} => T::WeightInfo::create_resource_specification(
+						name.len() as u32,
+						Self::longest(&[images.len(), resource_classified_as.len()]),
+					),
+					BatchItem::ConceptScheme { name } =>
+						T::WeightInfo::create_concept_scheme(name.len() as u32),
+					BatchItem::Concept { label, .. } =>
+						T::WeightInfo::create_concept(label.len() as u32),
+				})
+			})
+		}
+
+		/// Ensure every concept a record is classified as exists.
+		fn ensure_concepts_exist(concepts: &[ConceptId]) -> DispatchResult {
+			for concept_id in concepts {
+				ensure!(Concepts::<T>::contains_key(concept_id), Error::<T>::ConceptNotFound);
+			}
+
+			Ok(())
+		}
+
+		/// Ensure every unit a record refers to exists.
+		fn ensure_units_exist(units: &[Option<UnitId>]) -> DispatchResult {
+			for unit_id in units.iter().flatten() {
+				ensure!(Units::<T>::contains_key(unit_id), Error::<T>::UnitNotFound);
+			}
+
+			Ok(())
+		}
+
+		/// Whether a resource specification refers to the unit.
+		fn unit_in_use(unit_id: UnitId) -> bool {
+			UnitResourceSpecifications::<T>::iter_key_prefix(unit_id).next().is_some()
+		}
+
+		/// The concept followed by all concepts narrower than it, transitively.
+		pub fn concept_descendants(concept_id: ConceptId) -> Vec<ConceptId> {
+			// Broader concepts are fixed when a concept is created, so the hierarchy is acyclic.
+			let mut concepts = sp_std::vec![concept_id];
+			let mut next = 0;
+			while let Some(&concept_id) = concepts.get(next) {
+				concepts.extend(NarrowerConcepts::<T>::iter_key_prefix(concept_id));
+				next += 1;
+			}
+			concepts
+		}
+
+		/// The resource specifications classified as the concept or any concept narrower than it,
+		/// leaving out archived ones.
+		pub fn resource_specifications_classified_under(
+			concept_id: ConceptId,
+		) -> Vec<ResourceSpecificationId> {
+			let resource_spec_ids: BTreeSet<_> = Self::concept_descendants(concept_id)
+				.into_iter()
+				.flat_map(ClassifiedResourceSpecifications::<T>::iter_key_prefix)
+				.filter(|resource_spec_id| {
+					ResourceSpecifications::<T>::get(resource_spec_id)
+						.filter(|resource_spec| resource_spec.archived_at.is_none())
+						.is_some()
+				})
+				.collect();
+			resource_spec_ids.into_iter().collect()
+		}
+
+		/// Ensure every image is referenced by a CID the node's content store accepts, see
+		/// [`crate::cid::parse`], and is small enough to be stored there.
+		fn ensure_valid_images(images: &[Image<T>]) -> DispatchResult {
+			for image in images {
+				ensure!(crate::cid::parse(&image.cid).is_some(), Error::<T>::InvalidCid);
+				ensure!(image.size <= crate::cid::MAX_CONTENT_SIZE, Error::<T>::ImageTooLarge);
+			}
+
+			Ok(())
+		}
+
+		/// Request the preimages of the given text hashes so they are kept while referenced.
+		/// Fails unless every preimage has been noted, e.g. with `Preimage::note_preimage`.
+		///
+		/// Returns the total length of the texts. It is added to the length of the record
+		/// referencing them when computing its deposit, as the noter can take back the preimage
+		/// deposit once the text is requested.
+		fn request_texts(hashes: &[Option<H256>]) -> Result<usize, DispatchError> {
+			let mut len = 0;
+			for hash in hashes.iter().flatten() {
+				len += T::Preimages::len(hash).ok_or(Error::<T>::TextNotNoted)? as usize;
+			}
+			for hash in hashes.iter().flatten() {
+				T::Preimages::request(hash);
+			}
+
+			Ok(len)
+		}
+
+		/// The total length of the requested texts, see [`Self::request_texts`].
+		fn texts_len(hashes: &[Option<H256>]) -> usize {
+			hashes
+				.iter()
+				.flatten()
+				.filter_map(T::Preimages::len)
+				.map(|len| len as usize)
+				.sum()
+		}
+
+		/// Drop the requests taken by [`Self::request_texts`].
+		fn release_texts(hashes: &[Option<H256>]) {
+			for hash in hashes.iter().flatten() {
+				T::Preimages::unrequest(hash);
+			}
+		}
+
+		/// The text behind a hash stored in a record, if its preimage is available.
+		pub fn text(hash: &H256) -> Option<Vec<u8>> {
+			let len = T::Preimages::len(hash)?;
+			T::Preimages::fetch(hash, Some(len)).ok().map(|text| text.into_owned())
+		}
+
+		/// The largest of the given lengths, used as a weight component.
+		fn longest(lens: &[usize]) -> u32 {
+			lens.iter().copied().max().unwrap_or_default() as u32
+		}
+
+		/// A globally unique identifier of a record, derived from the genesis hash of this chain,
+		/// the record type and its local id. It can be used to reference the record from other
+		/// chains and in exports without colliding with records of other chains.
+		pub fn global_id<I: RecordId>(id: I) -> T::Hash {
+			let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+			id.global_id::<T::Hashing>(genesis_hash)
+		}
+
+		/// The deposit required to store a record of `len` encoded bytes, including its texts.
+		fn deposit_for(len: usize) -> BalanceOf<T> {
+			let len = BalanceOf::<T>::from(len as u32);
+			T::DepositBase::get().saturating_add(T::DepositPerByte::get().saturating_mul(len))
+		}
+
+		/// Reserve or release the difference between an old and a new deposit of `who`.
+		fn adjust_deposit(
+			who: &T::AccountId,
+			old: BalanceOf<T>,
+			new: BalanceOf<T>,
+		) -> DispatchResult {
+			if new > old {
+				T::Currency::reserve(who, new - old)?;
+			} else if old > new {
+				T::Currency::unreserve(who, old - new);
+			}
+
+			Ok(())
+		}
 	}
 }
+
+/// The owner and deposit held by every record.
+pub(crate) trait Owned<T: Config> {
+	fn owner_mut(&mut self) -> &mut T::AccountId;
+
+	fn deposit(&self) -> BalanceOf<T>;
+}
+
+macro_rules! owned {
+	($($record:ident),*) => {
+		$(
+			impl<T: Config> Owned<T> for $record<T> {
+				fn owner_mut(&mut self) -> &mut T::AccountId {
+					&mut self.owner
+				}
+
+				fn deposit(&self) -> BalanceOf<T> {
+					self.deposit
+				}
+			}
+		)*
+	};
+}
+
+owned!(Unit, SpatialThing, ProcessSpecification, ResourceSpecification, ConceptScheme, Concept);
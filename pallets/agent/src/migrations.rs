@@ -0,0 +1,249 @@
+//! Storage migrations for pallet-valueflows-agent
+
+use crate::*;
+use frame_support::{
+	traits::{
+		Get, GetStorageVersion, OnRuntimeUpgrade, QueryPreimage, StorageVersion, StorePreimage,
+	},
+	weights::Weight,
+	BoundedVec,
+};
+use sp_core::H256;
+use sp_runtime::{traits::Zero, FixedI64};
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	marker::PhantomData,
+	vec::Vec,
+};
+
+pub mod v1 {
+	use super::*;
+
+	#[derive(Decode)]
+	struct OldUnit {
+		label: Vec<u8>,
+		symbol: Vec<u8>,
+	}
+
+	#[derive(Decode)]
+	struct OldSpatialThing {
+		name: Vec<u8>,
+		note: Option<Vec<u8>>,
+		mappable_address: Option<Vec<u8>>,
+		lat: Option<FixedI64>,
+		long: Option<FixedI64>,
+		alt: Option<FixedI64>,
+	}
+
+	#[derive(Decode)]
+	struct OldProcessSpecification {
+		name: Vec<u8>,
+		note: Option<Vec<u8>>,
+	}
+
+	#[derive(Decode)]
+	struct OldResourceSpecification<Hash> {
+		name: Vec<u8>,
+		_images: Vec<Hash>,
+		note: Option<Vec<u8>>,
+		resource_classified_as: Vec<Vec<u8>>,
+		default_unit_of_resource_id: Option<u32>,
+		default_unit_of_effort_id: Option<u32>,
+	}
+
+	/// Name of the concept scheme holding the classifications of version 0.
+	pub const CLASSIFICATIONS: &[u8] = b"Classifications";
+
+	/// Migrate from version 0, where records had no owner and no deposit.
+	///
+	/// Records are kept under their ids, which stay valid along with their global ids, and the
+	/// id counters are left as they are. Every record is given to `Owner`, e.g. the sudo key, with
+	/// a zero deposit. Inline notes and addresses are noted as preimages without a deposit. The
+	/// free-form classifications of resource specifications become concepts of a
+	/// [`CLASSIFICATIONS`] scheme of `Owner`. Image hashes lack the CID, media type and size of an
+	/// [`Image`], so images are dropped, and default units that no longer exist are cleared.
+	pub struct MigrateToV1<T, Owner>(PhantomData<(T, Owner)>);
+
+	impl<T: Config, Owner: Get<T::AccountId>> OnRuntimeUpgrade for MigrateToV1<T, Owner> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let owner = Owner::get();
+			let mut owned = Vec::new();
+			let mut texts = BTreeSet::new();
+			let mut note = |text: Option<Vec<u8>>| -> Option<H256> {
+				let hash = T::Preimages::note(text?.into()).ok()?;
+				texts.insert(hash);
+				T::Preimages::request(&hash);
+				Some(hash)
+			};
+
+			Units::<T>::translate::<OldUnit, _>(|id, old| {
+				owned.push(CreatedRecord::Unit(id));
+				Some(Unit {
+					owner: owner.clone(),
+					deposit: Zero::zero(),
+					label: truncated(old.label),
+					symbol: truncated(old.symbol),
+				})
+			});
+
+			SpatialThings::<T>::translate::<OldSpatialThing, _>(|id, old| {
+				owned.push(CreatedRecord::SpatialThing(id));
+				Some(SpatialThing {
+					owner: owner.clone(),
+					deposit: Zero::zero(),
+					name: truncated(old.name),
+					note: note(old.note),
+					mappable_address: note(old.mappable_address),
+					lat: old.lat,
+					long: old.long,
+					alt: old.alt,
+				})
+			});
+
+			ProcessSpecifications::<T>::translate::<OldProcessSpecification, _>(|id, old| {
+				owned.push(CreatedRecord::ProcessSpecification(id));
+				Some(ProcessSpecification {
+					owner: owner.clone(),
+					deposit: Zero::zero(),
+					name: truncated(old.name),
+					note: note(old.note),
+					revision: 0,
+					previous_version: None,
+					archived_at: None,
+				})
+			});
+
+			let scheme_id = NextConceptSchemeId::<T>::get();
+			let first_concept_id = NextConceptId::<T>::get().0;
+			let mut concepts = BTreeMap::<BoundedVec<u8, T::MaxNameLength>, ConceptId>::new();
+			let mut classified = Vec::new();
+			let mut unit_references = Vec::new();
+			ResourceSpecifications::<T>::translate::<OldResourceSpecification<T::Hash>, _>(
+				|id, old| {
+					owned.push(CreatedRecord::ResourceSpecification(id));
+					let mut resource_classified_as = BoundedVec::default();
+					for label in old.resource_classified_as {
+						let next_id = ConceptId(first_concept_id + concepts.len() as u32);
+						let concept_id = *concepts.entry(truncated(label)).or_insert(next_id);
+						if !resource_classified_as.contains(&concept_id) {
+							let _ = resource_classified_as.try_push(concept_id);
+							classified.push((concept_id, id));
+						}
+					}
+					let mut unit = |unit_id: Option<u32>| {
+						let unit_id = unit_id
+							.map(UnitId)
+							.filter(|unit_id| Units::<T>::contains_key(unit_id))?;
+						unit_references.push((unit_id, id));
+						Some(unit_id)
+					};
+					Some(ResourceSpecification {
+						owner: owner.clone(),
+						deposit: Zero::zero(),
+						name: truncated(old.name),
+						images: BoundedVec::default(),
+						note: note(old.note),
+						resource_classified_as,
+						default_unit_of_resource_id: unit(old.default_unit_of_resource_id),
+						default_unit_of_effort_id: unit(old.default_unit_of_effort_id),
+						revision: 0,
+						previous_version: None,
+						archived_at: None,
+					})
+				},
+			);
+
+			if !concepts.is_empty() {
+				let scheme = ConceptScheme::<T> {
+					owner: owner.clone(),
+					deposit: Zero::zero(),
+					name: truncated(CLASSIFICATIONS.to_vec()),
+				};
+				ConceptSchemes::<T>::insert(scheme_id, scheme);
+				NextConceptSchemeId::<T>::put(ConceptSchemeId(scheme_id.0 + 1));
+				owned.push(CreatedRecord::ConceptScheme(scheme_id));
+				for (label, concept_id) in &concepts {
+					let concept = Concept::<T> {
+						owner: owner.clone(),
+						deposit: Zero::zero(),
+						scheme: scheme_id,
+						label: label.clone(),
+						broader: None,
+					};
+					Concepts::<T>::insert(concept_id, concept);
+					owned.push(CreatedRecord::Concept(*concept_id));
+				}
+				NextConceptId::<T>::put(ConceptId(first_concept_id + concepts.len() as u32));
+			}
+			for (concept_id, resource_spec_id) in &classified {
+				ClassifiedResourceSpecifications::<T>::insert(concept_id, resource_spec_id, ());
+			}
+			for (unit_id, resource_spec_id) in &unit_references {
+				UnitResourceSpecifications::<T>::insert(unit_id, resource_spec_id, ());
+			}
+
+			// Noting requested each text once more than the records referencing it.
+			for hash in &texts {
+				T::Preimages::unrequest(hash);
+			}
+			for record in &owned {
+				OwnedRecords::<T>::insert(&owner, record, ());
+			}
+			OwnedRecordCount::<T>::mutate(&owner, |count| {
+				*count = count.saturating_add(owned.len() as u32)
+			});
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			let records = owned.len() as u64;
+			let texts = texts.len() as u64;
+			let references = (classified.len() + unit_references.len()) as u64;
+			T::DbWeight::get()
+				.reads_writes(3 + records + references, 4 + 2 * records + references + 3 * texts)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok(counts::<T>().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let before =
+				<[u32; 8]>::decode(&mut &state[..]).map_err(|_| "pre_upgrade state must decode")?;
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"agent pallet must be at storage version 1"
+			);
+			frame_support::ensure!(
+				counts::<T>() == before,
+				"agent pallet records and id counters must be kept"
+			);
+			Ok(())
+		}
+	}
+
+	/// Truncate `bytes` to the bound `S`, which may be lower than the one of version 0.
+	fn truncated<S: Get<u32>>(mut bytes: Vec<u8>) -> BoundedVec<u8, S> {
+		bytes.truncate(S::get() as usize);
+		bytes.try_into().unwrap_or_default()
+	}
+
+	/// The number of records and the id counters of the record types of version 0.
+	#[cfg(feature = "try-runtime")]
+	fn counts<T: Config>() -> [u32; 8] {
+		[
+			Units::<T>::iter_keys().count() as u32,
+			SpatialThings::<T>::iter_keys().count() as u32,
+			ProcessSpecifications::<T>::iter_keys().count() as u32,
+			ResourceSpecifications::<T>::iter_keys().count() as u32,
+			NextUnitId::<T>::get().0,
+			NextSpatialThingId::<T>::get().0,
+			NextProcessSpecificationId::<T>::get().0,
+			NextResourceSpecificationId::<T>::get().0,
+		]
+	}
+}
@@ -1,6 +1,9 @@
-use crate as pallet_template;
-use frame_support::traits::{ConstU16, ConstU64};
-use frame_system as system;
+use crate as pallet_valueflows_agent;
+use frame_support::{
+	parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, GenesisBuild},
+};
+use frame_system::{self as system, EnsureRoot};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
@@ -10,6 +13,17 @@ use sp_runtime::{
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+
+pub const DEPOSIT_BASE: u64 = 10;
+pub const DEPOSIT_PER_BYTE: u64 = 1;
+
+pub const PREIMAGE_BASE_DEPOSIT: u64 = 5;
+pub const PREIMAGE_BYTE_DEPOSIT: u64 = 1;
+
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
 	pub enum Test where
@@ -18,7 +32,9 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system,
-		TemplateModule: pallet_template,
+		Balances: pallet_balances,
+		Preimage: pallet_preimage,
+		ValueflowsAgent: pallet_valueflows_agent,
 	}
 );
 
@@ -40,20 +56,111 @@ impl system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 	type SS58Prefix = ConstU16<42>;
 	type OnSetCode = ();
-	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
 }
 
-impl pallet_template::Config for Test {
+impl pallet_preimage::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<u64>;
+	type BaseDeposit = ConstU64<PREIMAGE_BASE_DEPOSIT>;
+	type ByteDeposit = ConstU64<PREIMAGE_BYTE_DEPOSIT>;
+}
+
+parameter_types! {
+	pub static MaxNameLength: u32 = 10;
+	pub static MaxArrayLength: u32 = 4;
+}
+
+impl pallet_valueflows_agent::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxNameLength = MaxNameLength;
+	type MaxSymbolLength = ConstU32<5>;
+	type MaxMimeTypeLength = ConstU32<16>;
+	type MaxArrayLength = MaxArrayLength;
+	type MaxCidLength = ConstU32<64>;
+	type Preimages = Preimage;
+	type Currency = Balances;
+	type DepositBase = ConstU64<DEPOSIT_BASE>;
+	type DepositPerByte = ConstU64<DEPOSIT_PER_BYTE>;
+	type MaxBatchLength = ConstU32<8>;
+	type RegistrationOrigin = EnsureRoot<u64>;
+	type MaxVouchers = ConstU32<3>;
+	type MaxOwnedRecords = ConstU32<16>;
+	type WeightInfo = ();
+}
+
+pub struct ExtBuilder {
+	balances: Vec<(u64, u64)>,
+	genesis: pallet_valueflows_agent::GenesisConfig<Test>,
+	max_name_length: u32,
+	max_array_length: u32,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![(ALICE, 1_000), (BOB, 1_000), (CHARLIE, 1_000)],
+			genesis: Default::default(),
+			max_name_length: 10,
+			max_array_length: 4,
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn max_name_length(mut self, max_name_length: u32) -> Self {
+		self.max_name_length = max_name_length;
+		self
+	}
+
+	pub fn max_array_length(mut self, max_array_length: u32) -> Self {
+		self.max_array_length = max_array_length;
+		self
+	}
+
+	pub fn genesis(mut self, genesis: pallet_valueflows_agent::GenesisConfig<Test>) -> Self {
+		self.genesis = genesis;
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		MaxNameLength::set(&self.max_name_length);
+		MaxArrayLength::set(&self.max_array_length);
+
+		let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: self.balances }
+			.assimilate_storage(&mut storage)
+			.unwrap();
+		self.genesis.assimilate_storage(&mut storage).unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(storage);
+		// Go past genesis block so events get deposited
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	ExtBuilder::default().build()
 }
@@ -1,4 +1,1716 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	migrations::v1::MigrateToV1, mock::*, AgentRegistrationMode, Agents, Application, Applications,
+	BatchItem, BatchRef, ClassifiedResourceSpecifications, ConceptId, ConceptSchemeId,
+	ConceptSchemes, Concepts, CreatedRecord, Error, Event, GenesisConfig, Image, KeyRotations,
+	NarrowerConcepts, NextProcessSpecificationId, NextResourceSpecificationId, NextSpatialThingId,
+	NextUnitId, OwnedRecordCount, OwnedRecords, ProcessSpecificationId, ProcessSpecifications,
+	RecordId, RegistrationMode, ResourceSpecificationId, ResourceSpecifications, SpatialThingId,
+	SpatialThings, UnitId, UnitResourceSpecifications, Units,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::migration,
+	traits::{ConstU64, Get, GetStorageVersion, OnRuntimeUpgrade, QueryPreimage, StorageVersion},
+	BoundedVec, StorageHasher, Twox64Concat,
+};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BadOrigin, BlakeTwo256, Hash},
+	FixedI64,
+};
 
+fn bounded<S: Get<u32>>(s: &[u8]) -> BoundedVec<u8, S> {
+	s.to_vec().try_into().unwrap()
+}
 
+/// Note `text` as a preimage paid for by `who` and return its hash.
+fn note(who: u64, text: &[u8]) -> H256 {
+	assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(who), text.to_vec()));
+	BlakeTwo256::hash(text)
+}
+
+fn preimage_deposit(text: &[u8]) -> u64 {
+	PREIMAGE_BASE_DEPOSIT + PREIMAGE_BYTE_DEPOSIT * text.len() as u64
+}
+
+fn register(who: u64) {
+	assert_ok!(ValueflowsAgent::register_agent(RuntimeOrigin::signed(who)));
+}
+
+fn create_unit(who: u64, label: &[u8], symbol: &[u8]) {
+	assert_ok!(ValueflowsAgent::create_unit(
+		RuntimeOrigin::signed(who),
+		bounded(label),
+		bounded(symbol),
+	));
+}
+
+fn create_spatial_thing(who: u64, name: &[u8]) {
+	assert_ok!(ValueflowsAgent::create_spatial_thing(
+		RuntimeOrigin::signed(who),
+		bounded(name),
+		None,
+		None,
+		None,
+		None,
+		None,
+	));
+}
+
+fn create_process_specification(who: u64, name: &[u8]) {
+	assert_ok!(ValueflowsAgent::create_process_specification(
+		RuntimeOrigin::signed(who),
+		bounded(name),
+		None,
+	));
+}
+
+fn create_resource_specification(who: u64, name: &[u8]) {
+	assert_ok!(ValueflowsAgent::create_resource_specification(
+		RuntimeOrigin::signed(who),
+		bounded(name),
+		Default::default(),
+		None,
+		Default::default(),
+		None,
+		None,
+	));
+}
+
+fn image(cid: &[u8]) -> Image<Test> {
+	Image { cid: bounded(cid), mime_type: bounded(b"image/png"), size: 1_024 }
+}
+
+/// A CIDv1 with the raw codec and a sha2-256 multihash.
+fn cid() -> Vec<u8> {
+	let mut cid = vec![0x01, 0x55, 0x12, 0x20];
+	cid.extend_from_slice(&[7; 32]);
+	cid
+}
+
+fn create_concept_scheme(who: u64, name: &[u8]) {
+	assert_ok!(ValueflowsAgent::create_concept_scheme(RuntimeOrigin::signed(who), bounded(name)));
+}
+
+fn create_concept(who: u64, scheme_id: u32, label: &[u8], broader: Option<u32>) {
+	assert_ok!(ValueflowsAgent::create_concept(
+		RuntimeOrigin::signed(who),
+		ConceptSchemeId(scheme_id),
+		bounded(label),
+		broader.map(ConceptId),
+	));
+}
+
+fn classify(who: u64, name: &[u8], concepts: &[u32]) {
+	let concepts: Vec<_> = concepts.iter().copied().map(ConceptId).collect();
+	assert_ok!(ValueflowsAgent::create_resource_specification(
+		RuntimeOrigin::signed(who),
+		bounded(name),
+		Default::default(),
+		None,
+		concepts.try_into().unwrap(),
+		None,
+		None,
+	));
+}
+
+fn deposit_of<R: Encode>(record: &R) -> u64 {
+	DEPOSIT_BASE + DEPOSIT_PER_BYTE * record.encoded_size() as u64
+}
+
+/// The part of a record deposit paying for a text it references.
+fn text_deposit(text: &[u8]) -> u64 {
+	DEPOSIT_PER_BYTE * text.len() as u64
+}
+
+#[test]
+fn register_agent_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValueflowsAgent::register_agent(RuntimeOrigin::signed(ALICE)));
+
+		assert_eq!(Agents::<Test>::get(ALICE), Some(true));
+		System::assert_last_event(Event::AgentRegistered(ALICE).into());
+	});
+}
+
+#[test]
+fn register_agent_twice_fails() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		assert_noop!(
+			ValueflowsAgent::register_agent(RuntimeOrigin::signed(ALICE)),
+			Error::<Test>::AgentAlreadyRegistered
+		);
+	});
+}
+
+fn set_registration_mode(mode: RegistrationMode) {
+	assert_ok!(ValueflowsAgent::set_registration_mode(RuntimeOrigin::root(), mode));
+}
+
+#[test]
+fn set_registration_mode_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(AgentRegistrationMode::<Test>::get(), RegistrationMode::Open);
+
+		assert_noop!(
+			ValueflowsAgent::set_registration_mode(
+				RuntimeOrigin::signed(ALICE),
+				RegistrationMode::Approved
+			),
+			BadOrigin
+		);
+		for required in [0, 4] {
+			assert_noop!(
+				ValueflowsAgent::set_registration_mode(
+					RuntimeOrigin::root(),
+					RegistrationMode::Vouched(required)
+				),
+				Error::<Test>::InvalidRegistrationMode
+			);
+		}
+
+		set_registration_mode(RegistrationMode::Vouched(3));
+		assert_eq!(AgentRegistrationMode::<Test>::get(), RegistrationMode::Vouched(3));
+		System::assert_last_event(Event::RegistrationModeSet(RegistrationMode::Vouched(3)).into());
+	});
+}
+
+#[test]
+fn approved_registration_works() {
+	new_test_ext().execute_with(|| {
+		set_registration_mode(RegistrationMode::Approved);
+
+		register(ALICE);
+		assert_eq!(Agents::<Test>::get(ALICE), None);
+		assert_eq!(
+			Applications::<Test>::get(ALICE),
+			Some(Application { deposit: DEPOSIT_BASE, vouchers: Default::default() })
+		);
+		assert_eq!(Balances::reserved_balance(ALICE), DEPOSIT_BASE);
+		System::assert_last_event(Event::RegistrationApplied(ALICE).into());
+		assert_noop!(
+			ValueflowsAgent::register_agent(RuntimeOrigin::signed(ALICE)),
+			Error::<Test>::AlreadyApplied
+		);
+
+		assert_noop!(
+			ValueflowsAgent::approve_application(RuntimeOrigin::signed(BOB), ALICE),
+			BadOrigin
+		);
+		assert_noop!(
+			ValueflowsAgent::approve_application(RuntimeOrigin::root(), BOB),
+			Error::<Test>::ApplicationNotFound
+		);
+		assert_ok!(ValueflowsAgent::approve_application(RuntimeOrigin::root(), ALICE));
+
+		assert_eq!(Agents::<Test>::get(ALICE), Some(true));
+		assert!(!Applications::<Test>::contains_key(ALICE));
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		System::assert_has_event(Event::ApplicationApproved(ALICE).into());
+		System::assert_last_event(Event::AgentRegistered(ALICE).into());
+	});
+}
+
+#[test]
+fn rejected_application_is_slashed() {
+	new_test_ext().execute_with(|| {
+		set_registration_mode(RegistrationMode::Approved);
+		register(ALICE);
+
+		assert_ok!(ValueflowsAgent::reject_application(RuntimeOrigin::root(), ALICE));
+
+		assert!(!Applications::<Test>::contains_key(ALICE));
+		assert_eq!(Agents::<Test>::get(ALICE), None);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::free_balance(ALICE), 1_000 - DEPOSIT_BASE);
+		System::assert_last_event(Event::ApplicationRejected(ALICE).into());
+	});
+}
+
+#[test]
+fn withdrawn_application_is_refunded() {
+	new_test_ext().execute_with(|| {
+		set_registration_mode(RegistrationMode::Approved);
+		assert_noop!(
+			ValueflowsAgent::withdraw_application(RuntimeOrigin::signed(ALICE)),
+			Error::<Test>::ApplicationNotFound
+		);
+		register(ALICE);
+
+		assert_ok!(ValueflowsAgent::withdraw_application(RuntimeOrigin::signed(ALICE)));
+
+		assert!(!Applications::<Test>::contains_key(ALICE));
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+		System::assert_last_event(Event::ApplicationWithdrawn(ALICE).into());
+	});
+}
+
+#[test]
+fn vouched_registration_works() {
+	new_test_ext().execute_with(|| {
+		register(BOB);
+		register(CHARLIE);
+		assert_noop!(
+			ValueflowsAgent::vouch(RuntimeOrigin::signed(BOB), ALICE),
+			Error::<Test>::VouchingDisabled
+		);
+		set_registration_mode(RegistrationMode::Vouched(2));
+		register(ALICE);
+
+		assert_noop!(
+			ValueflowsAgent::vouch(RuntimeOrigin::signed(DAVE), ALICE),
+			Error::<Test>::AgentIsNotRegistered
+		);
+		assert_noop!(
+			ValueflowsAgent::vouch(RuntimeOrigin::signed(BOB), DAVE),
+			Error::<Test>::ApplicationNotFound
+		);
+		assert_ok!(ValueflowsAgent::vouch(RuntimeOrigin::signed(BOB), ALICE));
+		System::assert_last_event(Event::Vouched(BOB, ALICE).into());
+		assert_noop!(
+			ValueflowsAgent::vouch(RuntimeOrigin::signed(BOB), ALICE),
+			Error::<Test>::AlreadyVouched
+		);
+		assert_eq!(Agents::<Test>::get(ALICE), None);
+
+		assert_ok!(ValueflowsAgent::vouch(RuntimeOrigin::signed(CHARLIE), ALICE));
+
+		assert_eq!(Agents::<Test>::get(ALICE), Some(true));
+		assert!(!Applications::<Test>::contains_key(ALICE));
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		System::assert_last_event(Event::AgentRegistered(ALICE).into());
+	});
+}
+
+#[test]
+fn get_my_agent_pubkey_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValueflowsAgent::get_my_agent_pubkey(RuntimeOrigin::signed(ALICE)));
+	});
+}
+
+#[test]
+fn unregistered_account_cannot_create_records() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValueflowsAgent::create_unit(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"kilogram"),
+				bounded(b"kg"),
+			),
+			Error::<Test>::AgentIsNotRegistered
+		);
+		assert_noop!(
+			ValueflowsAgent::create_spatial_thing(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"farm"),
+				None,
+				None,
+				None,
+				None,
+				None,
+			),
+			Error::<Test>::AgentIsNotRegistered
+		);
+		assert_noop!(
+			ValueflowsAgent::create_process_specification(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"harvest"),
+				None,
+			),
+			Error::<Test>::AgentIsNotRegistered
+		);
+		assert_noop!(
+			ValueflowsAgent::create_resource_specification(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"apple"),
+				Default::default(),
+				None,
+				Default::default(),
+				None,
+				None,
+			),
+			Error::<Test>::AgentIsNotRegistered
+		);
+	});
+}
+
+#[test]
+fn create_unit_works() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		create_unit(ALICE, b"kilogram", b"kg");
+
+		let unit = Units::<Test>::get(UnitId(0)).unwrap();
+		assert_eq!(unit.owner, ALICE);
+		assert_eq!(unit.label.to_vec(), b"kilogram".to_vec());
+		assert_eq!(unit.symbol.to_vec(), b"kg".to_vec());
+		assert_eq!(unit.deposit, deposit_of(&unit));
+		assert_eq!(Balances::reserved_balance(&ALICE), unit.deposit);
+		assert_eq!(NextUnitId::<Test>::get(), UnitId(1));
+	});
+}
+
+#[test]
+fn ids_are_counted_per_record_type() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+
+		create_unit(ALICE, b"kilogram", b"kg");
+		create_unit(BOB, b"hour", b"h");
+		create_spatial_thing(ALICE, b"farm");
+		create_process_specification(ALICE, b"harvest");
+
+		assert_eq!(Units::<Test>::get(UnitId(1)).unwrap().owner, BOB);
+		assert_eq!(NextUnitId::<Test>::get(), UnitId(2));
+		assert_eq!(NextSpatialThingId::<Test>::get(), SpatialThingId(1));
+		assert_eq!(NextProcessSpecificationId::<Test>::get(), ProcessSpecificationId(1));
+		assert_eq!(NextResourceSpecificationId::<Test>::get(), ResourceSpecificationId(0));
+	});
+}
+
+#[test]
+fn deleted_ids_are_not_reused() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		create_unit(ALICE, b"kilogram", b"kg");
+		assert_ok!(ValueflowsAgent::delete_unit(RuntimeOrigin::signed(ALICE), UnitId(0)));
+		create_unit(ALICE, b"hour", b"h");
+
+		assert!(!Units::<Test>::contains_key(UnitId(0)));
+		assert!(Units::<Test>::contains_key(UnitId(1)));
+	});
+}
+
+#[test]
+fn create_emits_event_with_new_id() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		create_unit(ALICE, b"kilogram", b"kg");
+		System::assert_last_event(Event::UnitCreated(UnitId(0), ALICE).into());
+
+		create_spatial_thing(ALICE, b"farm");
+		System::assert_last_event(Event::SpatialThingCreated(SpatialThingId(0), ALICE).into());
+
+		create_process_specification(ALICE, b"harvest");
+		System::assert_last_event(
+			Event::ProcessSpecificationCreated(ProcessSpecificationId(0), ALICE).into(),
+		);
+
+		create_resource_specification(ALICE, b"apple");
+		System::assert_last_event(
+			Event::ResourceSpecificationCreated(ResourceSpecificationId(0), ALICE).into(),
+		);
+	});
+}
+
+#[test]
+fn create_fails_when_ids_are_exhausted() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		NextUnitId::<Test>::put(UnitId(u32::MAX));
+
+		assert_noop!(
+			ValueflowsAgent::create_unit(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"kilogram"),
+				bounded(b"kg"),
+			),
+			Error::<Test>::IdOverflow
+		);
+	});
+}
+
+#[test]
+fn global_ids_differ_per_record_type() {
+	new_test_ext().execute_with(|| {
+		let unit = ValueflowsAgent::global_id(UnitId(0));
+
+		assert_eq!(unit, ValueflowsAgent::global_id(UnitId(0)));
+		assert_ne!(unit, ValueflowsAgent::global_id(UnitId(1)));
+		assert_ne!(unit, ValueflowsAgent::global_id(SpatialThingId(0)));
+
+		// Off chain the genesis hash is known without the pallet storage.
+		let genesis_hash = System::block_hash(0);
+		assert_eq!(UnitId(0).global_id::<BlakeTwo256>(genesis_hash), unit);
+	});
+}
+
+#[test]
+fn create_fails_without_funds_for_deposit() {
+	new_test_ext().execute_with(|| {
+		Agents::<Test>::insert(DAVE, true);
+
+		assert_noop!(
+			ValueflowsAgent::create_unit(
+				RuntimeOrigin::signed(DAVE),
+				bounded(b"kilogram"),
+				bounded(b"kg"),
+			),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_eq!(NextUnitId::<Test>::get(), UnitId(0));
+	});
+}
+
+#[test]
+fn update_unit_adjusts_deposit() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_unit(ALICE, b"kg", b"kg");
+		let small = Units::<Test>::get(UnitId(0)).unwrap().deposit;
+
+		assert_ok!(ValueflowsAgent::update_unit(
+			RuntimeOrigin::signed(ALICE),
+			UnitId(0),
+			bounded(b"kilogram"),
+			bounded(b"kg"),
+		));
+		System::assert_last_event(Event::UnitUpdated(UnitId(0)).into());
+		let unit = Units::<Test>::get(UnitId(0)).unwrap();
+		assert_eq!(unit.label.to_vec(), b"kilogram".to_vec());
+		assert_eq!(unit.deposit, deposit_of(&unit));
+		assert_eq!(unit.deposit, small + 6 * DEPOSIT_PER_BYTE);
+		assert_eq!(Balances::reserved_balance(&ALICE), unit.deposit);
+
+		assert_ok!(ValueflowsAgent::update_unit(
+			RuntimeOrigin::signed(ALICE),
+			UnitId(0),
+			bounded(b"kg"),
+			bounded(b"kg"),
+		));
+		assert_eq!(Units::<Test>::get(UnitId(0)).unwrap().deposit, small);
+		assert_eq!(Balances::reserved_balance(&ALICE), small);
+	});
+}
+
+#[test]
+fn update_unit_fails_for_missing_unit() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		assert_noop!(
+			ValueflowsAgent::update_unit(
+				RuntimeOrigin::signed(ALICE),
+				UnitId(0),
+				bounded(b"kilogram"),
+				bounded(b"kg"),
+			),
+			Error::<Test>::UnitNotFound
+		);
+	});
+}
+
+#[test]
+fn only_owner_can_update_or_delete_unit() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+		create_unit(ALICE, b"kilogram", b"kg");
+
+		assert_noop!(
+			ValueflowsAgent::update_unit(
+				RuntimeOrigin::signed(BOB),
+				UnitId(0),
+				bounded(b"gram"),
+				bounded(b"g"),
+			),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			ValueflowsAgent::delete_unit(RuntimeOrigin::signed(BOB), UnitId(0)),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn delete_unit_refunds_deposit() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_unit(ALICE, b"kilogram", b"kg");
+
+		assert_ok!(ValueflowsAgent::delete_unit(RuntimeOrigin::signed(ALICE), UnitId(0)));
+
+		assert!(!Units::<Test>::contains_key(UnitId(0)));
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		assert_eq!(Balances::free_balance(&ALICE), 1_000);
+		assert_noop!(
+			ValueflowsAgent::delete_unit(RuntimeOrigin::signed(ALICE), UnitId(0)),
+			Error::<Test>::UnitNotFound
+		);
+	});
+}
+
+#[test]
+fn spatial_thing_crud_works() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		let north = note(ALICE, b"north of the river");
+
+		assert_ok!(ValueflowsAgent::create_spatial_thing(
+			RuntimeOrigin::signed(ALICE),
+			bounded(b"farm"),
+			Some(north),
+			None,
+			Some(FixedI64::from_inner(52_520_008_000)),
+			Some(FixedI64::from_inner(13_404_954_000)),
+			None,
+		));
+		let spatial_thing = SpatialThings::<Test>::get(SpatialThingId(0)).unwrap();
+		assert_eq!(spatial_thing.owner, ALICE);
+		assert_eq!(spatial_thing.note, Some(north));
+		assert_eq!(ValueflowsAgent::text(&north), Some(b"north of the river".to_vec()));
+		assert_eq!(spatial_thing.lat, Some(FixedI64::from_inner(52_520_008_000)));
+		assert_eq!(NextSpatialThingId::<Test>::get(), SpatialThingId(1));
+
+		let address = note(ALICE, b"Main Street 1, Springfield");
+		assert_ok!(ValueflowsAgent::update_spatial_thing(
+			RuntimeOrigin::signed(ALICE),
+			SpatialThingId(0),
+			bounded(b"warehouse"),
+			None,
+			Some(address),
+			None,
+			None,
+			None,
+		));
+		let spatial_thing = SpatialThings::<Test>::get(SpatialThingId(0)).unwrap();
+		assert_eq!(spatial_thing.name.to_vec(), b"warehouse".to_vec());
+		assert_eq!(spatial_thing.note, None);
+		assert_eq!(spatial_thing.mappable_address, Some(address));
+		assert_eq!(spatial_thing.lat, None);
+		assert_eq!(
+			spatial_thing.deposit,
+			deposit_of(&spatial_thing) + text_deposit(b"Main Street 1, Springfield")
+		);
+		let text_deposits = preimage_deposit(b"north of the river") +
+			preimage_deposit(b"Main Street 1, Springfield");
+		assert_eq!(Balances::reserved_balance(&ALICE), spatial_thing.deposit + text_deposits);
+
+		assert_ok!(ValueflowsAgent::delete_spatial_thing(
+			RuntimeOrigin::signed(ALICE),
+			SpatialThingId(0)
+		));
+		assert!(!SpatialThings::<Test>::contains_key(SpatialThingId(0)));
+		assert_eq!(Balances::reserved_balance(&ALICE), text_deposits);
+	});
+}
+
+#[test]
+fn spatial_thing_errors() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+
+		assert_noop!(
+			ValueflowsAgent::delete_spatial_thing(RuntimeOrigin::signed(ALICE), SpatialThingId(0)),
+			Error::<Test>::SpatialThingNotFound
+		);
+
+		create_spatial_thing(ALICE, b"farm");
+		assert_noop!(
+			ValueflowsAgent::update_spatial_thing(
+				RuntimeOrigin::signed(BOB),
+				SpatialThingId(0),
+				bounded(b"barn"),
+				None,
+				None,
+				None,
+				None,
+				None,
+			),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			ValueflowsAgent::delete_spatial_thing(RuntimeOrigin::signed(BOB), SpatialThingId(0)),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn process_specification_crud_works() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		create_process_specification(ALICE, b"harvest");
+		let process_spec = ProcessSpecifications::<Test>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!(process_spec.owner, ALICE);
+		assert_eq!(process_spec.name.to_vec(), b"harvest".to_vec());
+		assert_eq!(NextProcessSpecificationId::<Test>::get(), ProcessSpecificationId(1));
+
+		let by_hand = note(ALICE, b"by hand");
+		assert_ok!(ValueflowsAgent::update_process_specification(
+			RuntimeOrigin::signed(ALICE),
+			ProcessSpecificationId(0),
+			bounded(b"pack"),
+			Some(by_hand),
+		));
+		let first = process_spec;
+		let process_spec = ProcessSpecifications::<Test>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!(process_spec.name.to_vec(), b"pack".to_vec());
+		assert_eq!(process_spec.note, Some(by_hand));
+		assert_eq!(process_spec.revision, 1);
+		assert_eq!(process_spec.previous_version, Some(BlakeTwo256::hash_of(&first)));
+		System::assert_last_event(
+			Event::ProcessSpecificationUpdated(ProcessSpecificationId(0), 1).into(),
+		);
+		let text_deposits = preimage_deposit(b"by hand") + text_deposit(b"by hand");
+		assert_eq!(Balances::reserved_balance(&ALICE), deposit_of(&process_spec) + text_deposits);
+
+		System::set_block_number(5);
+		assert_ok!(ValueflowsAgent::archive_process_specification(
+			RuntimeOrigin::signed(ALICE),
+			ProcessSpecificationId(0)
+		));
+		let updated = process_spec;
+		let process_spec = ProcessSpecifications::<Test>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!(process_spec.archived_at, Some(5));
+		assert_eq!(process_spec.revision, 2);
+		assert_eq!(process_spec.previous_version, Some(BlakeTwo256::hash_of(&updated)));
+		assert_eq!(process_spec.name.to_vec(), b"pack".to_vec());
+		assert_eq!(ValueflowsAgent::text(&by_hand), Some(b"by hand".to_vec()));
+		assert_eq!(Balances::reserved_balance(&ALICE), deposit_of(&process_spec) + text_deposits);
+	});
+}
+
+#[test]
+fn process_specification_errors() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+
+		assert_noop!(
+			ValueflowsAgent::update_process_specification(
+				RuntimeOrigin::signed(ALICE),
+				ProcessSpecificationId(0),
+				bounded(b"pack"),
+				None,
+			),
+			Error::<Test>::ProcessSpecificationNotFound
+		);
+
+		create_process_specification(ALICE, b"harvest");
+		assert_noop!(
+			ValueflowsAgent::archive_process_specification(
+				RuntimeOrigin::signed(BOB),
+				ProcessSpecificationId(0)
+			),
+			Error::<Test>::NotOwner
+		);
+
+		assert_ok!(ValueflowsAgent::archive_process_specification(
+			RuntimeOrigin::signed(ALICE),
+			ProcessSpecificationId(0)
+		));
+		assert_noop!(
+			ValueflowsAgent::update_process_specification(
+				RuntimeOrigin::signed(ALICE),
+				ProcessSpecificationId(0),
+				bounded(b"pack"),
+				None,
+			),
+			Error::<Test>::Archived
+		);
+		assert_noop!(
+			ValueflowsAgent::archive_process_specification(
+				RuntimeOrigin::signed(ALICE),
+				ProcessSpecificationId(0)
+			),
+			Error::<Test>::Archived
+		);
+	});
+}
+
+#[test]
+fn resource_specification_crud_works() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_unit(ALICE, b"kilogram", b"kg");
+		create_concept_scheme(ALICE, b"products");
+		create_concept(ALICE, 0, b"fruit", None);
+
+		assert_ok!(ValueflowsAgent::create_resource_specification(
+			RuntimeOrigin::signed(ALICE),
+			bounded(b"apple"),
+			vec![image(&cid())].try_into().unwrap(),
+			None,
+			vec![ConceptId(0)].try_into().unwrap(),
+			Some(UnitId(0)),
+			None,
+		));
+		let resource_spec =
+			ResourceSpecifications::<Test>::get(ResourceSpecificationId(0)).unwrap();
+		assert_eq!(resource_spec.owner, ALICE);
+		assert_eq!(resource_spec.images.to_vec(), vec![image(&cid())]);
+		assert_eq!(resource_spec.resource_classified_as.to_vec(), vec![ConceptId(0)]);
+		assert!(ClassifiedResourceSpecifications::<Test>::contains_key(
+			ConceptId(0),
+			ResourceSpecificationId(0)
+		));
+		assert_eq!(resource_spec.default_unit_of_resource_id, Some(UnitId(0)));
+		assert_eq!(NextResourceSpecificationId::<Test>::get(), ResourceSpecificationId(1));
+
+		let ripe = note(ALICE, b"ripe");
+		assert_ok!(ValueflowsAgent::update_resource_specification(
+			RuntimeOrigin::signed(ALICE),
+			ResourceSpecificationId(0),
+			bounded(b"pear"),
+			Default::default(),
+			Some(ripe),
+			Default::default(),
+			Some(UnitId(0)),
+			Some(UnitId(0)),
+		));
+		let resource_spec =
+			ResourceSpecifications::<Test>::get(ResourceSpecificationId(0)).unwrap();
+		assert_eq!(resource_spec.name.to_vec(), b"pear".to_vec());
+		assert!(resource_spec.images.is_empty());
+		assert_eq!(resource_spec.default_unit_of_effort_id, Some(UnitId(0)));
+		assert!(!ClassifiedResourceSpecifications::<Test>::contains_key(
+			ConceptId(0),
+			ResourceSpecificationId(0)
+		));
+		let other_deposits = Units::<Test>::get(UnitId(0)).unwrap().deposit +
+			ConceptSchemes::<Test>::get(ConceptSchemeId(0)).unwrap().deposit +
+			Concepts::<Test>::get(ConceptId(0)).unwrap().deposit +
+			preimage_deposit(b"ripe") +
+			text_deposit(b"ripe");
+		assert_eq!(Balances::reserved_balance(&ALICE), other_deposits + deposit_of(&resource_spec));
+
+		assert_eq!(resource_spec.revision, 1);
+		System::assert_last_event(
+			Event::ResourceSpecificationUpdated(ResourceSpecificationId(0), 1).into(),
+		);
+
+		assert_ok!(ValueflowsAgent::archive_resource_specification(
+			RuntimeOrigin::signed(ALICE),
+			ResourceSpecificationId(0)
+		));
+		System::assert_last_event(
+			Event::ResourceSpecificationArchived(ResourceSpecificationId(0)).into(),
+		);
+		let updated = resource_spec;
+		let resource_spec =
+			ResourceSpecifications::<Test>::get(ResourceSpecificationId(0)).unwrap();
+		assert_eq!(resource_spec.archived_at, Some(1));
+		assert_eq!(resource_spec.revision, 2);
+		assert_eq!(resource_spec.previous_version, Some(BlakeTwo256::hash_of(&updated)));
+		assert_eq!(Balances::reserved_balance(&ALICE), other_deposits + deposit_of(&resource_spec));
+	});
+}
+
+#[test]
+fn resource_specification_errors() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+
+		assert_noop!(
+			ValueflowsAgent::archive_resource_specification(
+				RuntimeOrigin::signed(ALICE),
+				ResourceSpecificationId(0)
+			),
+			Error::<Test>::ResourceSpecificationNotFound
+		);
+
+		create_resource_specification(ALICE, b"apple");
+		assert_noop!(
+			ValueflowsAgent::update_resource_specification(
+				RuntimeOrigin::signed(BOB),
+				ResourceSpecificationId(0),
+				bounded(b"pear"),
+				Default::default(),
+				None,
+				Default::default(),
+				None,
+				None,
+			),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn resource_specification_rejects_invalid_image_cids() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		// Unknown hash function (identity) and a digest of the wrong length.
+		let identity = [&[0x01, 0x55, 0x00, 0x20][..], &[7; 32]].concat();
+		let short_digest = [&cid()[..4], &[7; 31]].concat();
+		let invalid: [&[u8]; 5] =
+			[b"not a cid", &[0x12, 0x20, 1, 2, 3], &[0x01, 0x55], &identity, &short_digest];
+		for cid in invalid {
+			assert_noop!(
+				ValueflowsAgent::create_resource_specification(
+					RuntimeOrigin::signed(ALICE),
+					bounded(b"apple"),
+					vec![image(cid)].try_into().unwrap(),
+					None,
+					Default::default(),
+					None,
+					None,
+				),
+				Error::<Test>::InvalidCid
+			);
+		}
+
+		let mut too_large = image(&cid());
+		too_large.size = crate::cid::MAX_CONTENT_SIZE + 1;
+		assert_noop!(
+			ValueflowsAgent::create_resource_specification(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"apple"),
+				vec![too_large].try_into().unwrap(),
+				None,
+				Default::default(),
+				None,
+				None,
+			),
+			Error::<Test>::ImageTooLarge
+		);
+
+		// CIDv0 is a bare sha2-256 multihash.
+		let cid_v0 = cid()[2..].to_vec();
+		assert_ok!(ValueflowsAgent::create_resource_specification(
+			RuntimeOrigin::signed(ALICE),
+			bounded(b"apple"),
+			vec![image(&cid_v0)].try_into().unwrap(),
+			None,
+			Default::default(),
+			None,
+			None,
+		));
+		assert_noop!(
+			ValueflowsAgent::update_resource_specification(
+				RuntimeOrigin::signed(ALICE),
+				ResourceSpecificationId(0),
+				bounded(b"apple"),
+				vec![image(b"bad")].try_into().unwrap(),
+				None,
+				Default::default(),
+				None,
+				None,
+			),
+			Error::<Test>::InvalidCid
+		);
+	});
+}
+
+#[test]
+fn texts_must_be_noted_and_are_kept_while_referenced() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		let text = b"picked by hand, early in the morning";
+		let hash = BlakeTwo256::hash(text);
+
+		assert_noop!(
+			ValueflowsAgent::create_process_specification(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"harvest"),
+				Some(hash),
+			),
+			Error::<Test>::TextNotNoted
+		);
+
+		note(BOB, text);
+		assert_ok!(ValueflowsAgent::create_process_specification(
+			RuntimeOrigin::signed(ALICE),
+			bounded(b"harvest"),
+			Some(hash),
+		));
+		assert!(Preimage::is_requested(&hash));
+
+		// The noter gets the deposit back, but the text stays while it is referenced, paid for by
+		// the deposit of the record.
+		assert_ok!(Preimage::unnote_preimage(RuntimeOrigin::signed(BOB), hash));
+		assert_eq!(Balances::reserved_balance(&BOB), 0);
+		assert_eq!(ValueflowsAgent::text(&hash), Some(text.to_vec()));
+		let process_spec = ProcessSpecifications::<Test>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!(process_spec.deposit, deposit_of(&process_spec) + text_deposit(text));
+		assert_eq!(Balances::reserved_balance(&ALICE), process_spec.deposit);
+
+		assert_ok!(ValueflowsAgent::update_process_specification(
+			RuntimeOrigin::signed(ALICE),
+			ProcessSpecificationId(0),
+			bounded(b"harvest"),
+			None,
+		));
+		assert!(!Preimage::is_requested(&hash));
+		assert_eq!(ValueflowsAgent::text(&hash), None);
+		let process_spec = ProcessSpecifications::<Test>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!(Balances::reserved_balance(&ALICE), deposit_of(&process_spec));
+	});
+}
+
+#[test]
+fn concepts_form_a_hierarchy_within_a_scheme() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+		create_concept_scheme(ALICE, b"products");
+		create_concept_scheme(BOB, b"services");
+
+		create_concept(ALICE, 0, b"food", None);
+		create_concept(ALICE, 0, b"vegetables", Some(0));
+		System::assert_last_event(Event::ConceptCreated(ConceptId(1), ConceptSchemeId(0)).into());
+		assert_eq!(Concepts::<Test>::get(ConceptId(1)).unwrap().broader, Some(ConceptId(0)));
+		assert!(NarrowerConcepts::<Test>::contains_key(ConceptId(0), ConceptId(1)));
+
+		assert_noop!(
+			ValueflowsAgent::create_concept(
+				RuntimeOrigin::signed(ALICE),
+				ConceptSchemeId(2),
+				bounded(b"food"),
+				None,
+			),
+			Error::<Test>::ConceptSchemeNotFound
+		);
+		assert_noop!(
+			ValueflowsAgent::create_concept(
+				RuntimeOrigin::signed(BOB),
+				ConceptSchemeId(0),
+				bounded(b"fruit"),
+				None,
+			),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			ValueflowsAgent::create_concept(
+				RuntimeOrigin::signed(ALICE),
+				ConceptSchemeId(0),
+				bounded(b"fruit"),
+				Some(ConceptId(9)),
+			),
+			Error::<Test>::ConceptNotFound
+		);
+		create_concept(BOB, 1, b"delivery", None);
+		assert_noop!(
+			ValueflowsAgent::create_concept(
+				RuntimeOrigin::signed(ALICE),
+				ConceptSchemeId(0),
+				bounded(b"express"),
+				Some(ConceptId(2)),
+			),
+			Error::<Test>::ConceptSchemeMismatch
+		);
+	});
+}
+
+#[test]
+fn resource_specifications_must_reference_existing_concepts() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		assert_noop!(
+			ValueflowsAgent::create_resource_specification(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"carrot"),
+				Default::default(),
+				None,
+				vec![ConceptId(0)].try_into().unwrap(),
+				None,
+				None,
+			),
+			Error::<Test>::ConceptNotFound
+		);
+	});
+}
+
+#[test]
+fn resource_specifications_must_reference_existing_units() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_unit(ALICE, b"kilogram", b"kg");
+
+		assert_noop!(
+			ValueflowsAgent::create_resource_specification(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"carrot"),
+				Default::default(),
+				None,
+				Default::default(),
+				Some(UnitId(0)),
+				Some(UnitId(1)),
+			),
+			Error::<Test>::UnitNotFound
+		);
+
+		create_resource_specification(ALICE, b"carrot");
+		assert_noop!(
+			ValueflowsAgent::update_resource_specification(
+				RuntimeOrigin::signed(ALICE),
+				ResourceSpecificationId(0),
+				bounded(b"carrot"),
+				Default::default(),
+				None,
+				Default::default(),
+				Some(UnitId(1)),
+				None,
+			),
+			Error::<Test>::UnitNotFound
+		);
+	});
+}
+
+#[test]
+fn delete_unit_requires_it_to_be_unused() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+		create_unit(ALICE, b"kilogram", b"kg");
+		assert_ok!(ValueflowsAgent::create_resource_specification(
+			RuntimeOrigin::signed(BOB),
+			bounded(b"carrot"),
+			Default::default(),
+			None,
+			Default::default(),
+			Some(UnitId(0)),
+			Some(UnitId(0)),
+		));
+		assert!(UnitResourceSpecifications::<Test>::contains_key(
+			UnitId(0),
+			ResourceSpecificationId(0)
+		));
+
+		assert_noop!(
+			ValueflowsAgent::delete_unit(RuntimeOrigin::signed(ALICE), UnitId(0)),
+			Error::<Test>::UnitInUse
+		);
+
+		// Archived specifications keep their units.
+		assert_ok!(ValueflowsAgent::update_resource_specification(
+			RuntimeOrigin::signed(BOB),
+			ResourceSpecificationId(0),
+			bounded(b"carrot"),
+			Default::default(),
+			None,
+			Default::default(),
+			None,
+			Some(UnitId(0)),
+		));
+		assert_ok!(ValueflowsAgent::archive_resource_specification(
+			RuntimeOrigin::signed(BOB),
+			ResourceSpecificationId(0)
+		));
+		assert_noop!(
+			ValueflowsAgent::delete_unit(RuntimeOrigin::signed(ALICE), UnitId(0)),
+			Error::<Test>::UnitInUse
+		);
+
+		create_resource_specification(BOB, b"beetroot");
+		assert_ok!(ValueflowsAgent::update_resource_specification(
+			RuntimeOrigin::signed(BOB),
+			ResourceSpecificationId(1),
+			bounded(b"beetroot"),
+			Default::default(),
+			None,
+			Default::default(),
+			Some(UnitId(0)),
+			None,
+		));
+		assert_ok!(ValueflowsAgent::update_resource_specification(
+			RuntimeOrigin::signed(BOB),
+			ResourceSpecificationId(1),
+			bounded(b"beetroot"),
+			Default::default(),
+			None,
+			Default::default(),
+			None,
+			None,
+		));
+		assert!(!UnitResourceSpecifications::<Test>::contains_key(
+			UnitId(0),
+			ResourceSpecificationId(1)
+		));
+
+		create_unit(ALICE, b"metre", b"m");
+		assert_ok!(ValueflowsAgent::delete_unit(RuntimeOrigin::signed(ALICE), UnitId(1)));
+		assert!(!Units::<Test>::contains_key(UnitId(1)));
+	});
+}
+
+#[test]
+fn classified_under_includes_narrower_concepts() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_concept_scheme(ALICE, b"products");
+		create_concept(ALICE, 0, b"food", None);
+		create_concept(ALICE, 0, b"vegetables", Some(0));
+		create_concept(ALICE, 0, b"roots", Some(1));
+		create_concept(ALICE, 0, b"fruit", Some(0));
+
+		classify(ALICE, b"carrot", &[2]);
+		classify(ALICE, b"cabbage", &[1]);
+		classify(ALICE, b"apple", &[3]);
+		classify(ALICE, b"beetroot", &[1, 2]);
+
+		assert_eq!(
+			ValueflowsAgent::concept_descendants(ConceptId(1)),
+			vec![ConceptId(1), ConceptId(2)]
+		);
+		let classified_under = |concept_id| {
+			ValueflowsAgent::resource_specifications_classified_under(ConceptId(concept_id))
+				.into_iter()
+				.map(|id| id.0)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(classified_under(1), vec![0, 1, 3]);
+		assert_eq!(classified_under(2), vec![0, 3]);
+		assert_eq!(classified_under(0), vec![0, 1, 2, 3]);
+	});
+}
+
+#[test]
+fn delete_concept_requires_it_to_be_unused() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_concept_scheme(ALICE, b"products");
+		create_concept(ALICE, 0, b"food", None);
+		create_concept(ALICE, 0, b"vegetables", Some(0));
+		create_concept(ALICE, 0, b"fruit", Some(0));
+		classify(ALICE, b"carrot", &[1]);
+
+		assert_noop!(
+			ValueflowsAgent::delete_concept(RuntimeOrigin::signed(ALICE), ConceptId(0)),
+			Error::<Test>::ConceptInUse
+		);
+		assert_noop!(
+			ValueflowsAgent::delete_concept(RuntimeOrigin::signed(ALICE), ConceptId(1)),
+			Error::<Test>::ConceptInUse
+		);
+
+		// Archived specifications keep their concepts but are no longer listed under them.
+		assert_ok!(ValueflowsAgent::archive_resource_specification(
+			RuntimeOrigin::signed(ALICE),
+			ResourceSpecificationId(0)
+		));
+		let classified = ValueflowsAgent::resource_specifications_classified_under(ConceptId(0));
+		assert!(classified.is_empty());
+		assert_noop!(
+			ValueflowsAgent::delete_concept(RuntimeOrigin::signed(ALICE), ConceptId(1)),
+			Error::<Test>::ConceptInUse
+		);
+
+		let fruit_deposit = Concepts::<Test>::get(ConceptId(2)).unwrap().deposit;
+		let reserved = Balances::reserved_balance(&ALICE);
+		assert_ok!(ValueflowsAgent::delete_concept(RuntimeOrigin::signed(ALICE), ConceptId(2)));
+		assert!(!NarrowerConcepts::<Test>::contains_key(ConceptId(0), ConceptId(2)));
+		assert!(!Concepts::<Test>::contains_key(ConceptId(2)));
+		assert_eq!(Balances::reserved_balance(&ALICE), reserved - fruit_deposit);
+	});
+}
+
+fn batch(
+	items: Vec<BatchItem<Test>>,
+) -> BoundedVec<BatchItem<Test>, <Test as crate::Config>::MaxBatchLength> {
+	items.try_into().unwrap()
+}
+
+fn batch_unit(label: &[u8]) -> BatchItem<Test> {
+	BatchItem::Unit { label: bounded(label), symbol: bounded(b"u") }
+}
+
+fn batch_concept(scheme: BatchRef<ConceptSchemeId>, label: &[u8]) -> BatchItem<Test> {
+	BatchItem::Concept { scheme, label: bounded(label), broader: None }
+}
+
+#[test]
+fn batch_create_works() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_unit(ALICE, b"hour", b"h");
+
+		assert_ok!(ValueflowsAgent::batch_create(
+			RuntimeOrigin::signed(ALICE),
+			batch(vec![
+				batch_unit(b"kilogram"),
+				BatchItem::ConceptScheme { name: bounded(b"products") },
+				batch_concept(BatchRef::Batch(1), b"food"),
+				BatchItem::Concept {
+					scheme: BatchRef::Batch(1),
+					label: bounded(b"fruit"),
+					broader: Some(BatchRef::Batch(2)),
+				},
+				BatchItem::ResourceSpecification {
+					name: bounded(b"apple"),
+					images: Default::default(),
+					note: None,
+					resource_classified_as: vec![BatchRef::Batch(3)].try_into().unwrap(),
+					default_unit_of_resource_id: Some(BatchRef::Batch(0)),
+					default_unit_of_effort_id: Some(BatchRef::Existing(UnitId(0))),
+				},
+			]),
+		));
+
+		System::assert_last_event(
+			Event::BatchCreated(
+				vec![
+					CreatedRecord::Unit(UnitId(1)),
+					CreatedRecord::ConceptScheme(ConceptSchemeId(0)),
+					CreatedRecord::Concept(ConceptId(0)),
+					CreatedRecord::Concept(ConceptId(1)),
+					CreatedRecord::ResourceSpecification(ResourceSpecificationId(0)),
+				],
+				ALICE,
+			)
+			.into(),
+		);
+		assert_eq!(Concepts::<Test>::get(ConceptId(1)).unwrap().broader, Some(ConceptId(0)));
+		let resource_spec =
+			ResourceSpecifications::<Test>::get(ResourceSpecificationId(0)).unwrap();
+		assert_eq!(resource_spec.resource_classified_as.to_vec(), vec![ConceptId(1)]);
+		assert_eq!(resource_spec.default_unit_of_resource_id, Some(UnitId(1)));
+		assert_eq!(resource_spec.default_unit_of_effort_id, Some(UnitId(0)));
+		assert_eq!(
+			ValueflowsAgent::resource_specifications_classified_under(ConceptId(0)),
+			vec![ResourceSpecificationId(0)]
+		);
+	});
+}
+
+#[test]
+fn batch_create_is_atomic() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+		create_concept_scheme(BOB, b"services");
+
+		// The concept fails as the scheme is not owned by the caller, undoing the unit.
+		assert_noop!(
+			ValueflowsAgent::batch_create(
+				RuntimeOrigin::signed(ALICE),
+				batch(vec![
+					batch_unit(b"kilogram"),
+					batch_concept(BatchRef::Existing(ConceptSchemeId(0)), b"transport"),
+				]),
+			),
+			Error::<Test>::NotOwner
+		);
+
+		assert_noop!(
+			ValueflowsAgent::batch_create(
+				RuntimeOrigin::signed(CHARLIE),
+				batch(vec![batch_unit(b"kilogram")]),
+			),
+			Error::<Test>::AgentIsNotRegistered
+		);
+	});
+}
+
+#[test]
+fn batch_references_must_be_earlier_items_of_the_right_type() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+
+		// A later item.
+		assert_noop!(
+			ValueflowsAgent::batch_create(
+				RuntimeOrigin::signed(ALICE),
+				batch(vec![
+					batch_concept(BatchRef::Batch(1), b"food"),
+					BatchItem::ConceptScheme { name: bounded(b"products") },
+				]),
+			),
+			Error::<Test>::InvalidBatchReference
+		);
+		// The item itself.
+		assert_noop!(
+			ValueflowsAgent::batch_create(
+				RuntimeOrigin::signed(ALICE),
+				batch(vec![batch_concept(BatchRef::Batch(0), b"food")]),
+			),
+			Error::<Test>::InvalidBatchReference
+		);
+		// Another type of record.
+		assert_noop!(
+			ValueflowsAgent::batch_create(
+				RuntimeOrigin::signed(ALICE),
+				batch(vec![batch_unit(b"kilogram"), batch_concept(BatchRef::Batch(0), b"food")]),
+			),
+			Error::<Test>::InvalidBatchReference
+		);
+		// Records that do not exist.
+		let resource_spec = |unit: BatchRef<UnitId>, concept: BatchRef<ConceptId>| {
+			BatchItem::ResourceSpecification {
+				name: bounded(b"apple"),
+				images: Default::default(),
+				note: None,
+				resource_classified_as: vec![concept].try_into().unwrap(),
+				default_unit_of_resource_id: Some(unit),
+				default_unit_of_effort_id: None,
+			}
+		};
+		assert_noop!(
+			ValueflowsAgent::batch_create(
+				RuntimeOrigin::signed(ALICE),
+				batch(vec![
+					BatchItem::ConceptScheme { name: bounded(b"products") },
+					batch_concept(BatchRef::Batch(0), b"food"),
+					resource_spec(BatchRef::Existing(UnitId(0)), BatchRef::Batch(1)),
+				]),
+			),
+			Error::<Test>::UnitNotFound
+		);
+		assert_noop!(
+			ValueflowsAgent::batch_create(
+				RuntimeOrigin::signed(ALICE),
+				batch(vec![
+					batch_unit(b"kilogram"),
+					resource_spec(BatchRef::Batch(0), BatchRef::Existing(ConceptId(0))),
+				]),
+			),
+			Error::<Test>::ConceptNotFound
+		);
+	});
+}
+
+fn owned_records(who: u64) -> Vec<CreatedRecord> {
+	let mut records: Vec<_> = OwnedRecords::<Test>::iter_key_prefix(who).collect();
+	records.sort_by_key(|record| record.encode());
+	records
+}
+
+#[test]
+fn agents_own_at_most_max_owned_records() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		let max = <Test as crate::Config>::MaxOwnedRecords::get();
+		for _ in 0..max {
+			create_unit(ALICE, b"kilogram", b"kg");
+		}
+		assert_eq!(OwnedRecordCount::<Test>::get(ALICE), max);
+
+		assert_noop!(
+			ValueflowsAgent::create_unit(
+				RuntimeOrigin::signed(ALICE),
+				bounded(b"gram"),
+				bounded(b"g")
+			),
+			Error::<Test>::TooManyRecords
+		);
+
+		assert_ok!(ValueflowsAgent::delete_unit(RuntimeOrigin::signed(ALICE), UnitId(0)));
+		assert_eq!(OwnedRecordCount::<Test>::get(ALICE), max - 1);
+		create_unit(ALICE, b"gram", b"g");
+	});
+}
+
+#[test]
+fn deregister_agent_cleans_up_records() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_unit(ALICE, b"kilogram", b"kg");
+		let north = note(ALICE, b"north of the river");
+		assert_ok!(ValueflowsAgent::create_spatial_thing(
+			RuntimeOrigin::signed(ALICE),
+			bounded(b"farm"),
+			Some(north),
+			None,
+			None,
+			None,
+			None,
+		));
+		create_process_specification(ALICE, b"harvest");
+		create_concept_scheme(ALICE, b"products");
+		create_concept(ALICE, 0, b"fruit", None);
+		assert_eq!(owned_records(ALICE).len(), 5);
+
+		assert_noop!(
+			ValueflowsAgent::deregister_agent(RuntimeOrigin::signed(ALICE), 4),
+			Error::<Test>::InvalidRecordCount
+		);
+		assert_ok!(ValueflowsAgent::deregister_agent(RuntimeOrigin::signed(ALICE), 5));
+		System::assert_last_event(Event::AgentDeregistered(ALICE).into());
+
+		assert!(!Agents::<Test>::contains_key(ALICE));
+		assert!(!Units::<Test>::contains_key(UnitId(0)));
+		assert!(!SpatialThings::<Test>::contains_key(SpatialThingId(0)));
+		assert!(!Preimage::is_requested(&north));
+		let process_spec = ProcessSpecifications::<Test>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!(process_spec.archived_at, Some(1));
+		let scheme = ConceptSchemes::<Test>::get(ConceptSchemeId(0)).unwrap();
+		let concept = Concepts::<Test>::get(ConceptId(0)).unwrap();
+		assert_eq!(
+			owned_records(ALICE),
+			vec![
+				CreatedRecord::ProcessSpecification(ProcessSpecificationId(0)),
+				CreatedRecord::ConceptScheme(ConceptSchemeId(0)),
+				CreatedRecord::Concept(ConceptId(0)),
+			]
+		);
+		let kept_deposits = deposit_of(&process_spec) + scheme.deposit + concept.deposit;
+		assert_eq!(
+			Balances::reserved_balance(&ALICE),
+			kept_deposits + preimage_deposit(b"north of the river")
+		);
+
+		assert_noop!(
+			ValueflowsAgent::deregister_agent(RuntimeOrigin::signed(ALICE), 3),
+			Error::<Test>::AgentIsNotRegistered
+		);
+	});
+}
+
+#[test]
+fn deregister_agent_keeps_units_in_use() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+		create_unit(ALICE, b"kilogram", b"kg");
+		create_unit(ALICE, b"metre", b"m");
+		assert_ok!(ValueflowsAgent::create_resource_specification(
+			RuntimeOrigin::signed(BOB),
+			bounded(b"carrot"),
+			Default::default(),
+			None,
+			Default::default(),
+			Some(UnitId(0)),
+			None,
+		));
+
+		assert_ok!(ValueflowsAgent::deregister_agent(RuntimeOrigin::signed(ALICE), 2));
+
+		let unit = Units::<Test>::get(UnitId(0)).unwrap();
+		assert_eq!(unit.owner, ALICE);
+		assert!(!Units::<Test>::contains_key(UnitId(1)));
+		assert_eq!(owned_records(ALICE), vec![CreatedRecord::Unit(UnitId(0))]);
+		assert_eq!(Balances::reserved_balance(&ALICE), unit.deposit);
+	});
+}
+
+#[test]
+fn key_rotation_moves_records_to_the_new_account() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		create_unit(ALICE, b"kilogram", b"kg");
+		create_concept_scheme(ALICE, b"products");
+		let reserved = Balances::reserved_balance(&ALICE);
+
+		assert_noop!(
+			ValueflowsAgent::accept_key_rotation(RuntimeOrigin::signed(CHARLIE), ALICE, 2),
+			Error::<Test>::NoKeyRotation
+		);
+		assert_ok!(ValueflowsAgent::initiate_key_rotation(RuntimeOrigin::signed(ALICE), CHARLIE));
+		System::assert_last_event(Event::KeyRotationInitiated(ALICE, CHARLIE).into());
+		assert_noop!(
+			ValueflowsAgent::accept_key_rotation(RuntimeOrigin::signed(BOB), ALICE, 2),
+			Error::<Test>::NoKeyRotation
+		);
+		assert_noop!(
+			ValueflowsAgent::accept_key_rotation(RuntimeOrigin::signed(CHARLIE), ALICE, 1),
+			Error::<Test>::InvalidRecordCount
+		);
+
+		assert_ok!(ValueflowsAgent::accept_key_rotation(RuntimeOrigin::signed(CHARLIE), ALICE, 2));
+		System::assert_last_event(Event::AgentKeyRotated(ALICE, CHARLIE).into());
+		assert!(!Agents::<Test>::contains_key(ALICE));
+		assert_eq!(Agents::<Test>::get(CHARLIE), Some(true));
+		assert!(!KeyRotations::<Test>::contains_key(ALICE));
+		assert_eq!(Units::<Test>::get(UnitId(0)).unwrap().owner, CHARLIE);
+		assert_eq!(ConceptSchemes::<Test>::get(ConceptSchemeId(0)).unwrap().owner, CHARLIE);
+		assert!(owned_records(ALICE).is_empty());
+		assert_eq!(owned_records(CHARLIE).len(), 2);
+		assert!(!OwnedRecordCount::<Test>::contains_key(ALICE));
+		assert_eq!(OwnedRecordCount::<Test>::get(CHARLIE), 2);
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		assert_eq!(Balances::reserved_balance(&CHARLIE), reserved);
+
+		// The new account manages the records from now on.
+		assert_noop!(
+			ValueflowsAgent::delete_unit(RuntimeOrigin::signed(ALICE), UnitId(0)),
+			Error::<Test>::AgentIsNotRegistered
+		);
+		assert_ok!(ValueflowsAgent::delete_unit(RuntimeOrigin::signed(CHARLIE), UnitId(0)));
+		assert_eq!(owned_records(CHARLIE), vec![CreatedRecord::ConceptScheme(ConceptSchemeId(0))]);
+	});
+}
+
+#[test]
+fn key_rotation_to_an_agent_hands_records_over() {
+	new_test_ext().execute_with(|| {
+		register(ALICE);
+		register(BOB);
+		create_unit(ALICE, b"kilogram", b"kg");
+		create_unit(BOB, b"gram", b"g");
+
+		assert_ok!(ValueflowsAgent::initiate_key_rotation(RuntimeOrigin::signed(ALICE), BOB));
+		assert_ok!(ValueflowsAgent::accept_key_rotation(RuntimeOrigin::signed(BOB), ALICE, 1));
+
+		assert!(!Agents::<Test>::contains_key(ALICE));
+		assert_eq!(
+			owned_records(BOB),
+			vec![CreatedRecord::Unit(UnitId(0)), CreatedRecord::Unit(UnitId(1))]
+		);
+		assert_eq!(Units::<Test>::get(UnitId(0)).unwrap().owner, BOB);
+	});
+}
+
+#[test]
+fn bounds_are_configurable() {
+	ExtBuilder::default()
+		.max_name_length(32)
+		.max_array_length(1)
+		.build()
+		.execute_with(|| {
+			register(ALICE);
+
+			create_unit(ALICE, b"kilogram per square metre", b"kg/m2");
+			assert_eq!(Units::<Test>::get(UnitId(0)).unwrap().label.len(), 25);
+
+			let classifications: Result<BoundedVec<_, MaxArrayLength>, _> =
+				vec![ConceptId(0), ConceptId(1)].try_into();
+			assert!(classifications.is_err());
+
+			// Symbols keep their own bound.
+			let symbol: Result<BoundedVec<u8, <Test as crate::Config>::MaxSymbolLength>, _> =
+				b"kg/m^2".to_vec().try_into();
+			assert!(symbol.is_err());
+		});
+}
+
+#[test]
+fn genesis_config_creates_records() {
+	let genesis = GenesisConfig::<Test> {
+		agents: vec![ALICE, BOB],
+		registration_mode: RegistrationMode::Approved,
+		units: vec![(ALICE, b"kilogram".to_vec(), b"kg".to_vec())],
+		spatial_things: vec![(BOB, b"farm".to_vec(), None, None, None, None, None)],
+		process_specifications: vec![(BOB, b"harvest".to_vec(), Some(b"by hand".to_vec()))],
+		resource_specifications: vec![(
+			BOB,
+			b"apple".to_vec(),
+			None,
+			vec![ConceptId(1)],
+			Some(UnitId(0)),
+			None,
+		)],
+		concept_schemes: vec![(BOB, b"products".to_vec())],
+		concepts: vec![
+			(ConceptSchemeId(0), b"food".to_vec(), None),
+			(ConceptSchemeId(0), b"fruit".to_vec(), Some(ConceptId(0))),
+		],
+		archived_process_specifications: vec![ProcessSpecificationId(0)],
+		archived_resource_specifications: vec![],
+	};
+
+	ExtBuilder::default().genesis(genesis).build().execute_with(|| {
+		assert_eq!(Agents::<Test>::get(ALICE), Some(true));
+		assert_eq!(Agents::<Test>::get(BOB), Some(true));
+		assert_eq!(Agents::<Test>::get(CHARLIE), None);
+		assert_eq!(AgentRegistrationMode::<Test>::get(), RegistrationMode::Approved);
+
+		let unit = Units::<Test>::get(UnitId(0)).unwrap();
+		assert_eq!(unit.owner, ALICE);
+		assert_eq!(unit.label.to_vec(), b"kilogram".to_vec());
+		assert_eq!(Balances::reserved_balance(&ALICE), unit.deposit);
+
+		assert_eq!(SpatialThings::<Test>::get(SpatialThingId(0)).unwrap().owner, BOB);
+		assert_eq!(NextProcessSpecificationId::<Test>::get(), ProcessSpecificationId(1));
+		let process_spec = ProcessSpecifications::<Test>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!((process_spec.archived_at, process_spec.revision), (Some(0), 1));
+		let note = process_spec.note;
+		assert_eq!(ValueflowsAgent::text(&note.unwrap()), Some(b"by hand".to_vec()));
+		assert!(Preimage::is_requested(&note.unwrap()));
+		let resource_spec =
+			ResourceSpecifications::<Test>::get(ResourceSpecificationId(0)).unwrap();
+		assert_eq!(resource_spec.default_unit_of_resource_id, Some(UnitId(0)));
+		assert_eq!(Concepts::<Test>::get(ConceptId(1)).unwrap().owner, BOB);
+		assert_eq!(
+			ValueflowsAgent::resource_specifications_classified_under(ConceptId(0)),
+			vec![ResourceSpecificationId(0)]
+		);
+
+		// Genesis ids are followed by the ones created through extrinsics.
+		create_unit(ALICE, b"gram", b"g");
+		assert_eq!(NextUnitId::<Test>::get(), UnitId(2));
+	});
+}
+
+#[test]
+fn migration_to_v1_keeps_records() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<ValueflowsAgent>();
+		register(ALICE);
+		// Records of version 0, without an owner or deposit, and their id counters.
+		let key = Twox64Concat::hash(&0u32.encode());
+		let unit = (b"kg".to_vec(), b"kg".to_vec());
+		migration::put_storage_value(b"ValueflowsAgent", b"Units", &key, unit);
+		let process_spec = (b"harvest".to_vec(), Some(b"by hand".to_vec()));
+		migration::put_storage_value(
+			b"ValueflowsAgent",
+			b"ProcessSpecifications",
+			&key,
+			process_spec,
+		);
+		let resource_spec = (
+			b"apple".to_vec(),
+			vec![H256::repeat_byte(1)],
+			None::<Vec<u8>>,
+			vec![b"fruit".to_vec(), b"food".to_vec(), b"fruit".to_vec()],
+			Some(0u32),
+			Some(7u32),
+		);
+		migration::put_storage_value(
+			b"ValueflowsAgent",
+			b"ResourceSpecifications",
+			&key,
+			resource_spec,
+		);
+		for counter in [&b"UnitId"[..], b"ProcessSpecificationId", b"ResourceSpecificationId"] {
+			migration::put_storage_value(b"ValueflowsAgent", counter, &[], 1u32);
+		}
+
+		MigrateToV1::<Test, ConstU64<CHARLIE>>::on_runtime_upgrade();
+
+		assert_eq!(ValueflowsAgent::on_chain_storage_version(), 1);
+		assert_eq!(NextUnitId::<Test>::get(), UnitId(1));
+		assert_eq!(NextProcessSpecificationId::<Test>::get(), ProcessSpecificationId(1));
+		let unit = Units::<Test>::get(UnitId(0)).unwrap();
+		assert_eq!((unit.owner, unit.deposit, unit.label.to_vec()), (CHARLIE, 0, b"kg".to_vec()));
+		let process_spec = ProcessSpecifications::<Test>::get(ProcessSpecificationId(0)).unwrap();
+		assert_eq!((process_spec.owner, process_spec.revision), (CHARLIE, 0));
+		let note = process_spec.note.unwrap();
+		assert_eq!(ValueflowsAgent::text(&note), Some(b"by hand".to_vec()));
+		assert!(Preimage::is_requested(&note));
+
+		// Classifications become concepts of a new scheme, unknown units are cleared.
+		let resource_spec =
+			ResourceSpecifications::<Test>::get(ResourceSpecificationId(0)).unwrap();
+		assert!(resource_spec.images.is_empty());
+		assert_eq!(resource_spec.resource_classified_as.to_vec(), vec![ConceptId(0), ConceptId(1)]);
+		assert_eq!(
+			(resource_spec.default_unit_of_resource_id, resource_spec.default_unit_of_effort_id),
+			(Some(UnitId(0)), None)
+		);
+		assert!(UnitResourceSpecifications::<Test>::contains_key(
+			UnitId(0),
+			ResourceSpecificationId(0)
+		));
+		assert_eq!(ConceptSchemes::<Test>::get(ConceptSchemeId(0)).unwrap().owner, CHARLIE);
+		assert_eq!(Concepts::<Test>::get(ConceptId(1)).unwrap().label.to_vec(), b"food".to_vec());
+		assert_eq!(
+			ValueflowsAgent::resource_specifications_classified_under(ConceptId(0)),
+			vec![ResourceSpecificationId(0)]
+		);
+
+		assert_eq!(OwnedRecordCount::<Test>::get(CHARLIE), 6);
+		assert_eq!(Balances::reserved_balance(&CHARLIE), 0);
+		assert_eq!(Agents::<Test>::get(ALICE), Some(true));
+
+		// New records continue after the kept ones, and records of version 1 are left alone.
+		create_unit(ALICE, b"kilogram", b"kg");
+		assert_eq!(Units::<Test>::get(UnitId(1)).unwrap().owner, ALICE);
+		MigrateToV1::<Test, ConstU64<CHARLIE>>::on_runtime_upgrade();
+		assert_eq!(Units::<Test>::get(UnitId(1)).unwrap().owner, ALICE);
+	});
+}
@@ -0,0 +1,481 @@
+//! Weights for pallet_valueflows_agent
+//!
+//! These are not benchmark results. The values are estimated from the storage accesses of each
+//! call, listed above each function, and have to be replaced by the output of the benchmarks run
+//! on reference hardware with `scripts/benchmark.sh`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_valueflows_agent.
+pub trait WeightInfo {
+	fn get_my_agent_pubkey() -> Weight;
+	fn register_agent() -> Weight;
+	fn create_unit(s: u32, ) -> Weight;
+	fn update_unit(s: u32, ) -> Weight;
+	fn delete_unit() -> Weight;
+	fn create_spatial_thing(s: u32, ) -> Weight;
+	fn update_spatial_thing(s: u32, ) -> Weight;
+	fn delete_spatial_thing() -> Weight;
+	fn create_process_specification(s: u32, ) -> Weight;
+	fn update_process_specification(s: u32, ) -> Weight;
+	fn archive_process_specification() -> Weight;
+	fn create_resource_specification(s: u32, a: u32, ) -> Weight;
+	fn update_resource_specification(s: u32, a: u32, ) -> Weight;
+	fn archive_resource_specification() -> Weight;
+	fn create_concept_scheme(s: u32, ) -> Weight;
+	fn create_concept(s: u32, ) -> Weight;
+	fn delete_concept() -> Weight;
+	fn deregister_agent_spatial_things(r: u32, ) -> Weight;
+	fn deregister_agent_resource_specifications(r: u32, ) -> Weight;
+	fn initiate_key_rotation() -> Weight;
+	fn accept_key_rotation(r: u32, ) -> Weight;
+	fn set_registration_mode() -> Weight;
+	fn approve_application() -> Weight;
+	fn reject_application() -> Weight;
+	fn withdraw_application() -> Weight;
+	fn vouch() -> Weight;
+}
+
+/// Estimated weights for pallet_valueflows_agent, until it is benchmarked on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn get_my_agent_pubkey() -> Weight {
+		Weight::from_ref_time(9_000_000)
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:1)
+	// Storage: ValueflowsAgent AgentRegistrationMode (r:1 w:0)
+	// Storage: ValueflowsAgent Applications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn register_agent() -> Weight {
+		Weight::from_ref_time(30_000_000)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent NextUnitId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent Units (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn create_unit(s: u32, ) -> Weight {
+		Weight::from_ref_time(34_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent Units (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn update_unit(s: u32, ) -> Weight {
+		Weight::from_ref_time(36_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent Units (r:1 w:1)
+	// Storage: ValueflowsAgent UnitResourceSpecifications (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn delete_unit() -> Weight {
+		Weight::from_ref_time(37_000_000)
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent NextSpatialThingId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent SpatialThings (r:0 w:1)
+	// Storage: Preimage StatusFor (r:2 w:2)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn create_spatial_thing(s: u32, ) -> Weight {
+		Weight::from_ref_time(36_000_000)
+			.saturating_add(Weight::from_ref_time(3_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent SpatialThings (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Preimage StatusFor (r:4 w:4)
+	fn update_spatial_thing(s: u32, ) -> Weight {
+		Weight::from_ref_time(38_000_000)
+			.saturating_add(Weight::from_ref_time(3_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent SpatialThings (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Preimage StatusFor (r:2 w:2)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn delete_spatial_thing() -> Weight {
+		Weight::from_ref_time(36_000_000)
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent NextProcessSpecificationId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent ProcessSpecifications (r:0 w:1)
+	// Storage: Preimage StatusFor (r:1 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn create_process_specification(s: u32, ) -> Weight {
+		Weight::from_ref_time(34_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent ProcessSpecifications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Preimage StatusFor (r:2 w:2)
+	fn update_process_specification(s: u32, ) -> Weight {
+		Weight::from_ref_time(36_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent ProcessSpecifications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn archive_process_specification() -> Weight {
+		Weight::from_ref_time(32_000_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent NextResourceSpecificationId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent ResourceSpecifications (r:0 w:1)
+	// Storage: Preimage StatusFor (r:1 w:1)
+	// Storage: ValueflowsAgent Concepts (r:1 w:0)
+	// Storage: ValueflowsAgent Units (r:2 w:0)
+	// Storage: ValueflowsAgent ClassifiedResourceSpecifications (r:0 w:1)
+	// Storage: ValueflowsAgent UnitResourceSpecifications (r:0 w:2)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn create_resource_specification(s: u32, a: u32, ) -> Weight {
+		Weight::from_ref_time(37_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(Weight::from_ref_time(150_000).saturating_mul(a.into()))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(T::DbWeight::get().writes(8))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(a.into())))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent ResourceSpecifications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Preimage StatusFor (r:2 w:2)
+	// Storage: ValueflowsAgent Concepts (r:1 w:0)
+	// Storage: ValueflowsAgent Units (r:2 w:0)
+	// Storage: ValueflowsAgent ClassifiedResourceSpecifications (r:0 w:2)
+	// Storage: ValueflowsAgent UnitResourceSpecifications (r:0 w:4)
+	fn update_resource_specification(s: u32, a: u32, ) -> Weight {
+		Weight::from_ref_time(39_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(Weight::from_ref_time(200_000).saturating_mul(a.into()))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(T::DbWeight::get().writes(8))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(a.into())))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent ResourceSpecifications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn archive_resource_specification() -> Weight {
+		Weight::from_ref_time(34_000_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent NextConceptSchemeId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent ConceptSchemes (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn create_concept_scheme(s: u32, ) -> Weight {
+		Weight::from_ref_time(32_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent ConceptSchemes (r:1 w:0)
+	// Storage: ValueflowsAgent Concepts (r:1 w:1)
+	// Storage: ValueflowsAgent NextConceptId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent NarrowerConcepts (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn create_concept(s: u32, ) -> Weight {
+		Weight::from_ref_time(38_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent Concepts (r:1 w:1)
+	// Storage: ValueflowsAgent NarrowerConcepts (r:1 w:1)
+	// Storage: ValueflowsAgent ClassifiedResourceSpecifications (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	fn delete_concept() -> Weight {
+		Weight::from_ref_time(40_000_000)
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:1)
+	// Storage: ValueflowsAgent KeyRotations (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:1 w:1)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:1 w:1)
+	// Storage: ValueflowsAgent SpatialThings (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Preimage StatusFor (r:2 w:2)
+	fn deregister_agent_spatial_things(r: u32, ) -> Weight {
+		Weight::from_ref_time(30_000_000)
+			.saturating_add(Weight::from_ref_time(25_000_000).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(r.into())))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:1)
+	// Storage: ValueflowsAgent KeyRotations (r:0 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:1 w:0)
+	// Storage: ValueflowsAgent ResourceSpecifications (r:2 w:1)
+	// Storage: Preimage StatusFor (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	fn deregister_agent_resource_specifications(r: u32, ) -> Weight {
+		Weight::from_ref_time(30_000_000)
+			.saturating_add(Weight::from_ref_time(45_000_000).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(r.into())))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:0)
+	// Storage: ValueflowsAgent KeyRotations (r:0 w:1)
+	fn initiate_key_rotation() -> Weight {
+		Weight::from_ref_time(20_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: ValueflowsAgent KeyRotations (r:1 w:1)
+	// Storage: ValueflowsAgent OwnedRecords (r:1 w:2)
+	// Storage: ValueflowsAgent OwnedRecordCount (r:2 w:2)
+	// Storage: ValueflowsAgent ResourceSpecifications (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ValueflowsAgent Agents (r:0 w:2)
+	fn accept_key_rotation(r: u32, ) -> Weight {
+		Weight::from_ref_time(40_000_000)
+			.saturating_add(Weight::from_ref_time(40_000_000).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes(5))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(r.into())))
+	}
+	// Storage: ValueflowsAgent AgentRegistrationMode (r:0 w:1)
+	fn set_registration_mode() -> Weight {
+		Weight::from_ref_time(12_000_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: ValueflowsAgent Applications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ValueflowsAgent Agents (r:0 w:1)
+	fn approve_application() -> Weight {
+		Weight::from_ref_time(32_000_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: ValueflowsAgent Applications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn reject_application() -> Weight {
+		Weight::from_ref_time(34_000_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: ValueflowsAgent Applications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw_application() -> Weight {
+		Weight::from_ref_time(28_000_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: ValueflowsAgent Agents (r:1 w:1)
+	// Storage: ValueflowsAgent AgentRegistrationMode (r:1 w:0)
+	// Storage: ValueflowsAgent Applications (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn vouch() -> Weight {
+		Weight::from_ref_time(40_000_000)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn get_my_agent_pubkey() -> Weight {
+		Weight::from_ref_time(9_000_000)
+	}
+	fn register_agent() -> Weight {
+		Weight::from_ref_time(30_000_000)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn create_unit(s: u32, ) -> Weight {
+		Weight::from_ref_time(34_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn update_unit(s: u32, ) -> Weight {
+		Weight::from_ref_time(36_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn delete_unit() -> Weight {
+		Weight::from_ref_time(37_000_000)
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn create_spatial_thing(s: u32, ) -> Weight {
+		Weight::from_ref_time(36_000_000)
+			.saturating_add(Weight::from_ref_time(3_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+	fn update_spatial_thing(s: u32, ) -> Weight {
+		Weight::from_ref_time(38_000_000)
+			.saturating_add(Weight::from_ref_time(3_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn delete_spatial_thing() -> Weight {
+		Weight::from_ref_time(36_000_000)
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn create_process_specification(s: u32, ) -> Weight {
+		Weight::from_ref_time(34_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn update_process_specification(s: u32, ) -> Weight {
+		Weight::from_ref_time(36_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn archive_process_specification() -> Weight {
+		Weight::from_ref_time(32_000_000)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn create_resource_specification(s: u32, a: u32, ) -> Weight {
+		Weight::from_ref_time(37_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(Weight::from_ref_time(150_000).saturating_mul(a.into()))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(RocksDbWeight::get().writes(8))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(a.into())))
+	}
+	fn update_resource_specification(s: u32, a: u32, ) -> Weight {
+		Weight::from_ref_time(39_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(Weight::from_ref_time(200_000).saturating_mul(a.into()))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(RocksDbWeight::get().writes(8))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(a.into())))
+	}
+	fn archive_resource_specification() -> Weight {
+		Weight::from_ref_time(34_000_000)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn create_concept_scheme(s: u32, ) -> Weight {
+		Weight::from_ref_time(32_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn create_concept(s: u32, ) -> Weight {
+		Weight::from_ref_time(38_000_000)
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn delete_concept() -> Weight {
+		Weight::from_ref_time(40_000_000)
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(5))
+	}
+	fn deregister_agent_spatial_things(r: u32, ) -> Weight {
+		Weight::from_ref_time(30_000_000)
+			.saturating_add(Weight::from_ref_time(25_000_000).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(r.into())))
+			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(r.into())))
+	}
+	fn deregister_agent_resource_specifications(r: u32, ) -> Weight {
+		Weight::from_ref_time(30_000_000)
+			.saturating_add(Weight::from_ref_time(45_000_000).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(r.into())))
+			.saturating_add(RocksDbWeight::get().writes(2))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(r.into())))
+	}
+	fn initiate_key_rotation() -> Weight {
+		Weight::from_ref_time(20_000_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn accept_key_rotation(r: u32, ) -> Weight {
+		Weight::from_ref_time(40_000_000)
+			.saturating_add(Weight::from_ref_time(40_000_000).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(r.into())))
+			.saturating_add(RocksDbWeight::get().writes(5))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(r.into())))
+	}
+	fn set_registration_mode() -> Weight {
+		Weight::from_ref_time(12_000_000)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn approve_application() -> Weight {
+		Weight::from_ref_time(32_000_000)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn reject_application() -> Weight {
+		Weight::from_ref_time(34_000_000)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn withdraw_application() -> Weight {
+		Weight::from_ref_time(28_000_000)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn vouch() -> Weight {
+		Weight::from_ref_time(40_000_000)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+}
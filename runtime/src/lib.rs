@@ -40,7 +40,9 @@ pub use frame_support::{
 	StorageValue,
 };
 pub use frame_system::Call as SystemCall;
+use frame_system::EnsureRoot;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_preimage::Call as PreimageCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
@@ -49,6 +51,8 @@ pub use sp_runtime::{Perbill, Permill};
 
 /// Import the template pallet.
 pub use pallet_template;
+/// Import the ValueFlows agent pallet.
+pub use pallet_valueflows_agent;
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -103,9 +107,9 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// The version of the runtime specification. A full node will not attempt to use its native
 	//   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
-	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
-	//   the compatible custom types.
-	spec_version: 100,
+	// This value started at 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
+	//   the compatible custom types. Bump it with every change of storage or call encodings.
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -280,10 +284,31 @@ impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 }
 
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type BaseDeposit = ConstU128<{ 2 * EXISTENTIAL_DEPOSIT }>;
+	type ByteDeposit = ConstU128<10>;
+}
+
 impl pallet_valueflows_agent::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type MaxStringLength = ConstU32<10>;
+	type MaxNameLength = ConstU32<64>;
+	type MaxSymbolLength = ConstU32<16>;
+	type MaxMimeTypeLength = ConstU32<64>;
 	type MaxArrayLength = ConstU32<10>;
+	type MaxCidLength = ConstU32<64>;
+	type Preimages = Preimage;
+	type Currency = Balances;
+	type DepositBase = ConstU128<{ 2 * EXISTENTIAL_DEPOSIT }>;
+	type DepositPerByte = ConstU128<10>;
+	type MaxBatchLength = ConstU32<64>;
+	type RegistrationOrigin = EnsureRoot<AccountId>;
+	type MaxVouchers = ConstU32<16>;
+	type MaxOwnedRecords = ConstU32<1_000>;
+	type WeightInfo = pallet_valueflows_agent::weights::SubstrateWeight<Runtime>;
 }
 
 // impl pallet_valueflows_action::Config for Runtime {
@@ -306,6 +331,7 @@ construct_runtime!(
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
 		Sudo: pallet_sudo,
+		Preimage: pallet_preimage,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
 		ValueflowsAgent: pallet_valueflows_agent,
@@ -342,8 +368,23 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Storage migrations run on the next runtime upgrade.
+pub type Migrations =
+	(pallet_valueflows_agent::migrations::v1::MigrateToV1<Runtime, SudoKeyOrDefault>,);
+
+/// The sudo key, which is given the agent records created before records had owners. Without a
+/// sudo key they go to the all-zero account, which nobody controls.
+pub struct SudoKeyOrDefault;
+
+impl frame_support::traits::Get<AccountId> for SudoKeyOrDefault {
+	fn get() -> AccountId {
+		Sudo::key().unwrap_or_default()
+	}
+}
+
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
 extern crate frame_benchmarking;
@@ -354,8 +395,10 @@ mod benches {
 		[frame_benchmarking, BaselineBench::<Runtime>]
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
+		[pallet_preimage, Preimage]
 		[pallet_timestamp, Timestamp]
 		[pallet_template, TemplateModule]
+		[pallet_valueflows_agent, ValueflowsAgent]
 	);
 }
 
@@ -507,6 +550,18 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_valueflows_agent_rpc_runtime_api::AgentRuntimeApi<Block> for Runtime {
+		fn text(hash: sp_core::H256) -> Option<Vec<u8>> {
+			ValueflowsAgent::text(&hash)
+		}
+
+		fn resource_specifications_classified_under(
+			concept_id: pallet_valueflows_agent::ConceptId,
+		) -> Vec<pallet_valueflows_agent::ResourceSpecificationId> {
+			ValueflowsAgent::resource_specifications_classified_under(concept_id)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
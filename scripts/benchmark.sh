@@ -0,0 +1,17 @@
+#!/usr/bin/env bash
+# Benchmark the agent pallet on this machine and write its weights.
+# Run it on reference hardware from the root of the repository.
+set -e
+
+cargo build --release -p node-template --features runtime-benchmarks
+
+./target/release/node-template benchmark pallet \
+	--chain=dev \
+	--steps=50 \
+	--repeat=20 \
+	--pallet=pallet_valueflows_agent \
+	--extrinsic='*' \
+	--execution=wasm \
+	--wasm-execution=compiled \
+	--template=.maintain/frame-weight-template.hbs \
+	--output=pallets/agent/src/weights.rs